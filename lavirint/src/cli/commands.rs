@@ -0,0 +1,260 @@
+use std::{fs, io::{Error, ErrorKind, Write}, path::Path, time::Instant};
+
+use crate::maze::{Maze, format::MazeFormat, generator::GeneratorOptions, solver::Solver, validation::Severity, render::{Charset, RenderOptions, LEGEND}, svg::SvgOptions, raster::RasterOptions, graph::GraphKind};
+use crate::utilities::list_maze_files;
+
+use super::{Command, USAGE, EXIT_SUCCESS, EXIT_FAILURE};
+
+fn load_maze(input: &String, format: Option<MazeFormat>) -> Result<Maze, Error>{
+    let format = match format {
+        Some(format) => format,
+        None => MazeFormat::from_path(input)?
+    };
+    Maze::parse_with_format(input, format).map_err(|er| {
+        let kind = match er.kind() {
+            ErrorKind::InvalidInput => ErrorKind::InvalidData,
+            kind => kind
+        };
+        Error::new(kind, format!("Couldn't load the maze '{input}': {er}"))
+    })
+}
+
+pub fn path_to_moves(path: &[(usize, usize)]) -> String{
+    path.windows(2)
+        .map(|step| {
+            let (from, to) = (step[0], step[1]);
+            if to.0 < from.0 {
+                "U"
+            }else if to.0 > from.0 {
+                "D"
+            }else if to.1 < from.1 {
+                "L"
+            }else{
+                "R"
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+pub fn path_to_string(path: &[(usize, usize)]) -> String{
+    path.iter()
+        .map(|(row, col)| format!("({row}, {col})"))
+        .collect::<Vec<String>>()
+        .join(" -> ")
+}
+
+impl Command{
+    pub fn run(self) -> Result<i32, Error>{
+        match self {
+            Command::Help => {
+                println!("{USAGE}");
+                Ok(EXIT_SUCCESS)
+            },
+            Command::Convert{ input, output, from, to } => convert(&input, &output, from, to),
+            Command::Validate{ input, format } => validate(&input, format),
+            Command::Solve{ input, format, solver, moves } => solve(&input, format, solver, moves),
+            Command::Generate{ options, output, format } => generate(&options, output, format),
+            Command::Bench{ input, format, runs } => bench(&input, format, runs),
+            Command::Render{ input, format, solution, solver, unicode } => render(&input, format, solution, solver, unicode),
+            Command::Svg{ input, output, format, cell_size, solution, solver, coordinates, icons } => {
+                let options = SvgOptions{ cell_size, coordinates, icons, ..Default::default() };
+                if Path::new(&input).is_dir() {
+                    svg_pack(&input, output, &options, solver)
+                }else{
+                    svg(&input, output, format, options, solution, solver)
+                }
+            },
+            Command::Png{ input, output, format, cell_size, solution, solver } => png(&input, &output, format, cell_size, solution, solver),
+            Command::Dot{ input, output, format, graph, solver } => dot(&input, output, format, graph, solver),
+            Command::Play{ input, format, solver, grid, fog } => play(input, format, solver, grid, fog),
+            Command::Tui{ input, format, solver } => tui(&input, format, solver)
+        }
+    }
+}
+
+fn convert(input: &String, output: &String, from: Option<MazeFormat>, to: Option<MazeFormat>) -> Result<i32, Error>{
+    let maze = load_maze(input, from)?;
+    let to = match to {
+        Some(format) => format,
+        None => MazeFormat::from_path(output)?
+    };
+    maze.save_with_format(output, to)?;
+    Ok(EXIT_SUCCESS)
+}
+
+fn validate(input: &String, format: Option<MazeFormat>) -> Result<i32, Error>{
+    let maze = load_maze(input, format)?;
+    let issues = maze.validate();
+    for issue in issues.iter(){
+        println!("{issue}");
+    }
+    if issues.iter().any(|issue| issue.severity == Severity::Error) {
+        Ok(EXIT_FAILURE)
+    }else{
+        println!("{input}: OK ({} warnings)", issues.len());
+        Ok(EXIT_SUCCESS)
+    }
+}
+
+fn solve(input: &String, format: Option<MazeFormat>, solver: Solver, moves: bool) -> Result<i32, Error>{
+    let mut maze = load_maze(input, format)?;
+    let state = maze.get_state_mut().clone();
+    match solver.solve(&maze, state) {
+        Some(path) => {
+            if moves {
+                println!("{}", path_to_moves(&path));
+            }else{
+                println!("{}", path_to_string(&path));
+            }
+            Ok(EXIT_SUCCESS)
+        },
+        None => {
+            eprintln!("No path found!");
+            Ok(EXIT_FAILURE)
+        }
+    }
+}
+
+fn generate(options: &GeneratorOptions, output: Option<String>, format: Option<MazeFormat>) -> Result<i32, Error>{
+    let maze = Maze::generate(options);
+    match output {
+        Some(output) => {
+            let format = match format {
+                Some(format) => format,
+                None => MazeFormat::from_path(&output)?
+            };
+            maze.save_with_format(&output, format)?;
+        },
+        None => {
+            let data = maze.to_format_vector(format.unwrap_or(MazeFormat::Txt))?;
+            std::io::stdout().write_all(&data)?;
+        }
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn bench(input: &String, format: Option<MazeFormat>, runs: usize) -> Result<i32, Error>{
+    let mut maze = load_maze(input, format)?;
+    let state = maze.get_state_mut().clone();
+    let mut found = true;
+    for solver in Solver::ALL{
+        let now = Instant::now();
+        for _ in 0..runs{
+            found &= solver.solve(&maze, state.clone()).is_some();
+        }
+        println!("{}: {:.6}s average over {runs} runs", solver.name(), now.elapsed().as_secs_f64() / runs as f64);
+    }
+    Ok(if found { EXIT_SUCCESS } else { EXIT_FAILURE })
+}
+
+fn render(input: &String, format: Option<MazeFormat>, solution: bool, solver: Solver, unicode: bool) -> Result<i32, Error>{
+    let mut maze = load_maze(input, format)?;
+    let state = maze.get_state_mut().clone();
+    let path = if solution { solver.solve(&maze, state) } else { None };
+    let options = RenderOptions{
+        charset: if unicode { Charset::Unicode } else { Charset::Ascii },
+        path: path.as_deref(),
+        ..Default::default()
+    };
+    println!("{}", maze.render(&options));
+    println!("{LEGEND}");
+    if solution && path.is_none() {
+        eprintln!("No path found!");
+        return Ok(EXIT_FAILURE);
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn svg(input: &String, output: Option<String>, format: Option<MazeFormat>, options: SvgOptions, solution: bool, solver: Solver) -> Result<i32, Error>{
+    let mut maze = load_maze(input, format)?;
+    let state = maze.get_state_mut().clone();
+    let path = if solution { solver.solve(&maze, state) } else { None };
+    let options = SvgOptions{ path: path.as_deref(), ..options };
+    match output {
+        Some(output) => maze.save_svg(&output, &options)?,
+        None => print!("{}", maze.to_svg(&options))
+    }
+    if solution && path.is_none() {
+        eprintln!("No path found!");
+        return Ok(EXIT_FAILURE);
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn svg_pack(input: &String, output: Option<String>, options: &SvgOptions, solver: Solver) -> Result<i32, Error>{
+    let output = match output {
+        Some(output) => output,
+        None => return Err(Error::new(ErrorKind::InvalidInput, "Exporting a directory needs an --output directory!"))
+    };
+    fs::create_dir_all(&output)?;
+    let mut code = EXIT_SUCCESS;
+    for file in list_maze_files(input)?{
+        let name = file.file_stem().and_then(|stem| stem.to_str()).unwrap_or("level").to_string();
+        let mut maze = load_maze(&file.to_string_lossy().to_string(), None)?;
+        let state = maze.get_state_mut().clone();
+        let path = solver.solve(&maze, state);
+        if path.is_none() {
+            eprintln!("{name}: no path found, the answer sheet is left blank");
+            code = EXIT_FAILURE;
+        }
+
+        let puzzle = Path::new(&output).join(format!("{name}.svg"));
+        maze.save_svg(&puzzle.to_string_lossy(), &SvgOptions{ title: Some(&name), ..options.clone() })?;
+        let title = format!("{name} - answers");
+        let answers = Path::new(&output).join(format!("{name}-answers.svg"));
+        maze.save_svg(&answers.to_string_lossy(), &SvgOptions{ title: Some(&title), path: path.as_deref(), ..options.clone() })?;
+        println!("{} {}", puzzle.display(), answers.display());
+    }
+    Ok(code)
+}
+
+fn png(input: &String, output: &str, format: Option<MazeFormat>, cell_size: u32, solution: bool, solver: Solver) -> Result<i32, Error>{
+    let mut maze = load_maze(input, format)?;
+    let state = maze.get_state_mut().clone();
+    let path = if solution { solver.solve(&maze, state) } else { None };
+    maze.save_png(output, &RasterOptions{ cell_size, path: path.as_deref(), ..Default::default() })?;
+    if solution && path.is_none() {
+        eprintln!("No path found!");
+        return Ok(EXIT_FAILURE);
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn dot(input: &String, output: Option<String>, format: Option<MazeFormat>, graph: GraphKind, solver: Solver) -> Result<i32, Error>{
+    let mut maze = load_maze(input, format)?;
+    let state = maze.get_state_mut().clone();
+    let path = if graph == GraphKind::States { solver.solve(&maze, state) } else { None };
+    let dot = maze.to_dot(graph, path.as_deref());
+    match output {
+        Some(output) => fs::write(output, dot)?,
+        None => print!("{dot}")
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+#[cfg(feature = "gui")]
+fn play(input: Option<String>, format: Option<MazeFormat>, solver: Solver, grid: bool, fog: bool) -> Result<i32, Error>{
+    let (maze, visibility) = match input {
+        Some(input) => (Some(load_maze(&input, format)?), crate::utilities::read_visibility_radius(Path::new(&input))),
+        None => (None, None)
+    };
+    crate::visualize::display(maze, solver, grid, fog, visibility);
+    Ok(EXIT_SUCCESS)
+}
+
+#[cfg(not(feature = "gui"))]
+fn play(_input: Option<String>, _format: Option<MazeFormat>, _solver: Solver, _grid: bool, _fog: bool) -> Result<i32, Error>{
+    Err(Error::new(ErrorKind::Unsupported, "Built without the 'gui' feature, rebuild with '--features gui' to play!"))
+}
+
+#[cfg(feature = "tui")]
+fn tui(input: &String, format: Option<MazeFormat>, solver: Solver) -> Result<i32, Error>{
+    crate::tui::play(load_maze(input, format)?, solver)?;
+    Ok(EXIT_SUCCESS)
+}
+
+#[cfg(not(feature = "tui"))]
+fn tui(_input: &String, _format: Option<MazeFormat>, _solver: Solver) -> Result<i32, Error>{
+    Err(Error::new(ErrorKind::Unsupported, "Built without the 'tui' feature, rebuild with '--features tui' to play in the terminal!"))
+}
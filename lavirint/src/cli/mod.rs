@@ -0,0 +1,303 @@
+pub mod commands;
+
+use std::{collections::{HashMap, HashSet}, io::{Error, ErrorKind}, str::FromStr};
+
+use crate::maze::{format::MazeFormat, generator::GeneratorOptions, solver::Solver, graph::GraphKind};
+
+pub const USAGE: &str = "Usage: maze <COMMAND> [OPTIONS]
+
+Commands:
+  convert <INPUT> <OUTPUT> [--from FORMAT] [--to FORMAT]    Translate a maze between formats
+  validate <FILE> [--format FORMAT]                         Lint a maze
+  solve <FILE> [--format FORMAT] [--solver SOLVER] [--moves]  Print the shortest path or the moves
+  generate [--seed N] [--rows N] [--columns N] [--doors N] [--keys N] [--exits N] [--output FILE] [--format FORMAT]
+                                                            Generate a maze from a seed
+  bench <FILE> [--format FORMAT] [--runs N]                 Time every solver
+  render <FILE> [--format FORMAT] [--solution] [--solver SOLVER] [--unicode]
+                                                            Draw the maze, optionally with the shortest path
+  svg <FILE|DIR> [--output PATH] [--format FORMAT] [--cell-size N] [--solution] [--solver SOLVER] [--coordinates] [--no-icons]
+                                                            Export an SVG, a directory is exported as puzzle and answer sheets
+  png <FILE> --output FILE [--format FORMAT] [--cell-size N] [--solution] [--solver SOLVER]
+                                                            Render a PNG without a window or GPU
+  dot <FILE> [--graph GRAPH] [--output FILE] [--format FORMAT] [--solver SOLVER]
+                                                            Export a GraphViz graph of the maze
+  play [FILE] [--format FORMAT] [--solver SOLVER] [--grid] [--fog]
+                                                            Launch the game (needs the 'gui' feature), '--grid' moves one field per key press,
+                                                            '--fog' hides fields the player hasn't seen (radius from FILE.fog, default 4)
+  tui <FILE> [--format FORMAT] [--solver SOLVER]            Play in the terminal (needs the 'tui' feature)
+
+Formats: txt, bin (6x9 mazes only), lab (any size)
+Solvers: sequential, parallel, a-star (default)
+Graphs: passages (fields and doors), states (explored states with the shortest path), regions (areas separated by doors)
+
+Exit codes:
+  0  success
+  1  no path found or the maze failed validation
+  2  invalid command-line usage
+  3  file couldn't be read or written
+  4  malformed maze data
+  5  command unavailable in this build";
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_IO: i32 = 3;
+pub const EXIT_DATA: i32 = 4;
+pub const EXIT_UNSUPPORTED: i32 = 5;
+
+const VALUE_OPTIONS: &[&str] = &["--format", "--from", "--to", "--solver", "--seed", "--rows", "--columns", "--doors", "--keys", "--exits", "--output", "--runs", "--cell-size", "--graph"];
+const FLAG_OPTIONS: &[&str] = &["--moves", "--solution", "--unicode", "--coordinates", "--no-icons", "--grid", "--fog", "--help"];
+
+#[derive(Debug)]
+pub enum Command{
+    Convert{ input: String, output: String, from: Option<MazeFormat>, to: Option<MazeFormat> },
+    Validate{ input: String, format: Option<MazeFormat> },
+    Solve{ input: String, format: Option<MazeFormat>, solver: Solver, moves: bool },
+    Generate{ options: GeneratorOptions, output: Option<String>, format: Option<MazeFormat> },
+    Bench{ input: String, format: Option<MazeFormat>, runs: usize },
+    Render{ input: String, format: Option<MazeFormat>, solution: bool, solver: Solver, unicode: bool },
+    Svg{ input: String, output: Option<String>, format: Option<MazeFormat>, cell_size: f32, solution: bool, solver: Solver, coordinates: bool, icons: bool },
+    Png{ input: String, output: String, format: Option<MazeFormat>, cell_size: u32, solution: bool, solver: Solver },
+    Dot{ input: String, output: Option<String>, format: Option<MazeFormat>, graph: GraphKind, solver: Solver },
+    Play{ input: Option<String>, format: Option<MazeFormat>, solver: Solver, grid: bool, fog: bool },
+    Tui{ input: String, format: Option<MazeFormat>, solver: Solver },
+    Help
+}
+
+struct ParsedArguments{
+    positional: Vec<String>,
+    values: HashMap<String, String>,
+    flags: HashSet<String>
+}
+
+fn usage_error(message: String) -> Error{
+    Error::new(ErrorKind::InvalidInput, format!("{message}\n\n{USAGE}"))
+}
+
+fn expand_alias(arg: &str) -> &str{
+    match arg {
+        "-f" => "--format",
+        "-s" => "--solver",
+        "-o" => "--output",
+        "-h" => "--help",
+        _ => arg
+    }
+}
+
+impl ParsedArguments{
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, Error>{
+        let mut parsed = ParsedArguments{ positional: Vec::new(), values: HashMap::new(), flags: HashSet::new() };
+        let mut args = args.peekable();
+
+        while let Some(arg) = args.next(){
+            let option = expand_alias(&arg).to_string();
+            if VALUE_OPTIONS.contains(&option.as_str()) {
+                match args.next() {
+                    Some(value) => parsed.values.insert(option, value),
+                    None => return Err(usage_error(format!("Option '{arg}' expects a value!")))
+                };
+            }else if FLAG_OPTIONS.contains(&option.as_str()) {
+                parsed.flags.insert(option);
+            }else if arg.starts_with('-') {
+                return Err(usage_error(format!("Unknown option '{arg}'!")));
+            }else{
+                parsed.positional.push(arg);
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn allow(&self, command: &str, allowed: &[&str], positional: std::ops::RangeInclusive<usize>) -> Result<(), Error>{
+        for option in self.values.keys().chain(self.flags.iter()){
+            if !allowed.contains(&option.as_str()) {
+                return Err(usage_error(format!("Option '{option}' isn't supported by '{command}'!")));
+            }
+        }
+        if !positional.contains(&self.positional.len()) {
+            return Err(usage_error(format!("Wrong number of arguments for '{command}'!")));
+        }
+        Ok(())
+    }
+
+    fn value<T: FromStr>(&self, option: &str) -> Result<Option<T>, Error>{
+        match self.values.get(option) {
+            Some(value) => match value.parse::<T>() {
+                Ok(value) => Ok(Some(value)),
+                Err(_) => Err(usage_error(format!("Invalid value '{value}' for '{option}'!")))
+            },
+            None => Ok(None)
+        }
+    }
+
+    fn format(&self, option: &str) -> Result<Option<MazeFormat>, Error>{
+        match self.values.get(option) {
+            Some(value) => Ok(Some(MazeFormat::from_str(value)?)),
+            None => Ok(None)
+        }
+    }
+
+    fn solver(&self) -> Result<Solver, Error>{
+        match self.values.get("--solver") {
+            Some(value) => Solver::from_str(value),
+            None => Ok(Solver::default())
+        }
+    }
+}
+
+impl Command{
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Self, Error>{
+        let mut args = args.skip(1);
+        let name = match args.next() {
+            Some(name) => name,
+            None => return Ok(Command::Play{ input: None, format: None, solver: Solver::default(), grid: false, fog: false })
+        };
+        if name == "-h" || name == "--help" || name == "help" {
+            return Ok(Command::Help);
+        }
+
+        let parsed = ParsedArguments::parse(args)?;
+        if parsed.flags.contains("--help") {
+            return Ok(Command::Help);
+        }
+
+        match name.as_str() {
+            "convert" => {
+                parsed.allow("convert", &["--from", "--to"], 2..=2)?;
+                Ok(Command::Convert{
+                    input: parsed.positional[0].clone(),
+                    output: parsed.positional[1].clone(),
+                    from: parsed.format("--from")?,
+                    to: parsed.format("--to")?
+                })
+            },
+            "validate" => {
+                parsed.allow("validate", &["--format"], 1..=1)?;
+                Ok(Command::Validate{ input: parsed.positional[0].clone(), format: parsed.format("--format")? })
+            },
+            "solve" => {
+                parsed.allow("solve", &["--format", "--solver", "--moves"], 1..=1)?;
+                Ok(Command::Solve{
+                    input: parsed.positional[0].clone(),
+                    format: parsed.format("--format")?,
+                    solver: parsed.solver()?,
+                    moves: parsed.flags.contains("--moves")
+                })
+            },
+            "generate" => {
+                parsed.allow("generate", &["--seed", "--rows", "--columns", "--doors", "--keys", "--exits", "--output", "--format"], 0..=0)?;
+                let defaults = GeneratorOptions::default();
+                let options = GeneratorOptions{
+                    seed: parsed.value("--seed")?.unwrap_or(defaults.seed),
+                    dimensions: (
+                        parsed.value("--rows")?.unwrap_or(defaults.dimensions.0),
+                        parsed.value("--columns")?.unwrap_or(defaults.dimensions.1)
+                    ),
+                    doors: parsed.value("--doors")?.unwrap_or(defaults.doors),
+                    keys: parsed.value("--keys")?.unwrap_or(defaults.keys),
+                    exits: parsed.value("--exits")?.unwrap_or(defaults.exits)
+                };
+                if options.dimensions.0 == 0 || options.dimensions.1 == 0 {
+                    return Err(usage_error("Maze dimensions can't be zero!".to_string()));
+                }
+                Ok(Command::Generate{ options, output: parsed.values.get("--output").cloned(), format: parsed.format("--format")? })
+            },
+            "bench" => {
+                parsed.allow("bench", &["--format", "--runs"], 1..=1)?;
+                Ok(Command::Bench{
+                    input: parsed.positional[0].clone(),
+                    format: parsed.format("--format")?,
+                    runs: parsed.value("--runs")?.unwrap_or(5).max(1)
+                })
+            },
+            "render" => {
+                parsed.allow("render", &["--format", "--solution", "--solver", "--unicode"], 1..=1)?;
+                Ok(Command::Render{
+                    input: parsed.positional[0].clone(),
+                    format: parsed.format("--format")?,
+                    solution: parsed.flags.contains("--solution"),
+                    solver: parsed.solver()?,
+                    unicode: parsed.flags.contains("--unicode")
+                })
+            },
+            "svg" => {
+                parsed.allow("svg", &["--output", "--format", "--cell-size", "--solution", "--solver", "--coordinates", "--no-icons"], 1..=1)?;
+                let cell_size: f32 = parsed.value("--cell-size")?.unwrap_or(40.);
+                if cell_size <= 0. {
+                    return Err(usage_error("Cell size has to be positive!".to_string()));
+                }
+                Ok(Command::Svg{
+                    input: parsed.positional[0].clone(),
+                    output: parsed.values.get("--output").cloned(),
+                    format: parsed.format("--format")?,
+                    cell_size,
+                    solution: parsed.flags.contains("--solution"),
+                    solver: parsed.solver()?,
+                    coordinates: parsed.flags.contains("--coordinates"),
+                    icons: !parsed.flags.contains("--no-icons")
+                })
+            },
+            "png" => {
+                parsed.allow("png", &["--output", "--format", "--cell-size", "--solution", "--solver"], 1..=1)?;
+                let output = match parsed.values.get("--output") {
+                    Some(output) => output.clone(),
+                    None => return Err(usage_error("'png' needs an --output file!".to_string()))
+                };
+                let cell_size: u32 = parsed.value("--cell-size")?.unwrap_or(32);
+                if cell_size == 0 {
+                    return Err(usage_error("Cell size has to be positive!".to_string()));
+                }
+                Ok(Command::Png{
+                    input: parsed.positional[0].clone(),
+                    output,
+                    format: parsed.format("--format")?,
+                    cell_size,
+                    solution: parsed.flags.contains("--solution"),
+                    solver: parsed.solver()?
+                })
+            },
+            "dot" => {
+                parsed.allow("dot", &["--graph", "--output", "--format", "--solver"], 1..=1)?;
+                let graph = match parsed.values.get("--graph") {
+                    Some(value) => GraphKind::from_str(value)?,
+                    None => GraphKind::default()
+                };
+                Ok(Command::Dot{
+                    input: parsed.positional[0].clone(),
+                    output: parsed.values.get("--output").cloned(),
+                    format: parsed.format("--format")?,
+                    graph,
+                    solver: parsed.solver()?
+                })
+            },
+            "play" => {
+                parsed.allow("play", &["--format", "--solver", "--grid", "--fog"], 0..=1)?;
+                Ok(Command::Play{
+                    input: parsed.positional.first().cloned(),
+                    format: parsed.format("--format")?,
+                    solver: parsed.solver()?,
+                    grid: parsed.flags.contains("--grid"),
+                    fog: parsed.flags.contains("--fog")
+                })
+            },
+            "tui" => {
+                parsed.allow("tui", &["--format", "--solver"], 1..=1)?;
+                Ok(Command::Tui{
+                    input: parsed.positional[0].clone(),
+                    format: parsed.format("--format")?,
+                    solver: parsed.solver()?
+                })
+            },
+            _ => Err(usage_error(format!("Unknown command '{name}'!")))
+        }
+    }
+}
+
+pub fn exit_code(error: &Error) -> i32{
+    match error.kind() {
+        ErrorKind::InvalidInput => EXIT_USAGE,
+        ErrorKind::InvalidData => EXIT_DATA,
+        ErrorKind::Unsupported => EXIT_UNSUPPORTED,
+        _ => EXIT_IO
+    }
+}
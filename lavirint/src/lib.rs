@@ -0,0 +1,9 @@
+// Stefan Aleksić E2-42-2022
+
+pub mod utilities;
+pub mod maze;
+pub mod cli;
+#[cfg(feature = "gui")]
+pub mod visualize;
+#[cfg(feature = "tui")]
+pub mod tui;
@@ -0,0 +1,51 @@
+use std::fmt::Display;
+
+use super::{Maze, state::State};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty{
+    Easy,
+    Medium,
+    Hard,
+    Unsolvable
+}
+
+impl Display for Difficulty{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Unsolvable => "Unsolvable"
+        })
+    }
+}
+
+impl Maze{
+    pub fn difficulty(&self) -> Difficulty{
+        let mut state = State::create_from_maze(self);
+        let field = self.distance_field_from(&state);
+
+        let moves = match field.moves_left(&state) {
+            Some(moves) => moves,
+            None => return Difficulty::Unsolvable
+        };
+
+        let mut doors_opened = 0;
+        while let Some(next) = field.next_move(self, &state){
+            if state.doors_graph.get(&state.position).unwrap().contains(&next) {
+                doors_opened += 1;
+            }
+            state = state.transfer_state(&next).unwrap();
+        }
+        let ratio = moves as f32 / (self.dimensions.0 * self.dimensions.1) as f32;
+
+        if ratio >= 0.5 || doors_opened >= 2 {
+            Difficulty::Hard
+        }else if ratio >= 0.25 || doors_opened == 1 {
+            Difficulty::Medium
+        }else{
+            Difficulty::Easy
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{Maze, state::State, search::{signature, Signature}};
+
+// Minimum number of moves to an exit, found by searching backwards from every exit
+// through all the states reachable from the starting one
+#[derive(Clone, Debug, Default)]
+pub struct DistanceField{
+    pub dimensions: (usize, usize),
+    pub reachable_states: usize,
+    states: HashMap<Signature, usize>,
+    cells: HashMap<((usize, usize), usize), usize>
+}
+
+impl DistanceField{
+    pub fn moves_left(&self, state: &State) -> Option<usize>{
+        self.states.get(&signature(state)).copied()
+    }
+
+    // Best case over the keys that are still lying around and the doors that are still locked
+    pub fn get(&self, position: (usize, usize), keys: usize) -> Option<usize>{
+        self.cells.get(&(position, keys)).copied()
+    }
+
+    // Best case for every position over any number of keys, collected in one pass
+    pub fn best(&self) -> HashMap<(usize, usize), usize>{
+        let mut best = HashMap::new();
+        for (&(position, _), &distance) in self.cells.iter(){
+            best.entry(position)
+                .and_modify(|current: &mut usize| *current = (*current).min(distance))
+                .or_insert(distance);
+        }
+        best
+    }
+
+    pub fn max_distance(&self) -> usize{
+        self.cells.values().copied().max().unwrap_or(0)
+    }
+
+    // States the player can get into but never out of, like spending the last key on the wrong door
+    pub fn dead_ends(&self) -> usize{
+        self.reachable_states - self.states.len()
+    }
+
+    pub fn next_move(&self, maze: &Maze, state: &State) -> Option<(usize, usize)>{
+        let distance = self.moves_left(state)?;
+        let mut neighbours: Vec<(usize, usize)> = maze.get_direct_neighbours(&state.position).into_iter().collect();
+        neighbours.sort();
+        neighbours.into_iter().find(|neighbour| {
+            state.transfer_state(neighbour)
+                .and_then(|next| self.moves_left(&next))
+                .is_some_and(|next_distance| next_distance + 1 == distance)
+        })
+    }
+}
+
+impl Maze{
+    pub fn distance_field(&self) -> DistanceField{
+        self.distance_field_from(&State::create_from_maze(self))
+    }
+
+    pub fn distance_field_from(&self, state: &State) -> DistanceField{
+        let walls_graph = self.get_walls_graph();
+        let mut states = vec![state.clone()];
+        let mut index = HashMap::from([(signature(state), 0)]);
+        let mut reverse_edges: Vec<Vec<usize>> = vec![Vec::new()];
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(current) = queue.pop_front(){
+            if self.exits.contains(&states[current].position) {
+                continue;
+            }
+            let mut neighbours: Vec<&(usize, usize)> = walls_graph.get(&states[current].position).unwrap().iter().collect();
+            neighbours.sort();
+            for node in neighbours{
+                if let Some(new_state) = states[current].transfer_state(node) {
+                    let key = signature(&new_state);
+                    let next = match index.get(&key) {
+                        Some(next) => *next,
+                        None => {
+                            index.insert(key, states.len());
+                            states.push(new_state);
+                            reverse_edges.push(Vec::new());
+                            queue.push_back(states.len() - 1);
+                            states.len() - 1
+                        }
+                    };
+                    reverse_edges[next].push(current);
+                }
+            }
+        }
+
+        let mut distances: Vec<Option<usize>> = vec![None; states.len()];
+        let mut queue = VecDeque::new();
+        for (i, state) in states.iter().enumerate(){
+            if self.exits.contains(&state.position) {
+                distances[i] = Some(0);
+                queue.push_back(i);
+            }
+        }
+        while let Some(current) = queue.pop_front(){
+            let distance = distances[current].unwrap();
+            for previous in reverse_edges[current].iter(){
+                if distances[*previous].is_none() {
+                    distances[*previous] = Some(distance + 1);
+                    queue.push_back(*previous);
+                }
+            }
+        }
+
+        let mut field = DistanceField{ dimensions: self.dimensions, reachable_states: states.len(), ..Default::default() };
+        for (state, distance) in states.iter().zip(distances){
+            if let Some(distance) = distance {
+                field.states.insert(signature(state), distance);
+                let best = field.cells.entry((state.position, state.keys)).or_insert(distance);
+                *best = (*best).min(distance);
+            }
+        }
+        field
+    }
+}
@@ -0,0 +1,114 @@
+use std::{fs::File, io::{Error, ErrorKind, Write}, path::Path, str::FromStr};
+
+use crate::utilities::convert_string_to_u8;
+
+use super::{Maze, DEFAULT_ROWS, DEFAULT_COLUMNS, DEFAULT_DIRECTIONS, DEFAULT_KEY, DEFAULT_END};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MazeFormat{
+    Txt,
+    Bin,
+    Lab
+}
+
+impl FromStr for MazeFormat{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "txt" => Ok(MazeFormat::Txt),
+            "bin" => Ok(MazeFormat::Bin),
+            "lab" => Ok(MazeFormat::Lab),
+            _ => Err(Error::new(ErrorKind::InvalidInput, format!("Unknown maze format '{s}'!")))
+        }
+    }
+}
+
+impl MazeFormat{
+    pub fn from_path(file_path: &String) -> Result<Self, Error>{
+        match Path::new(file_path).extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => MazeFormat::from_str(ext),
+            None => Err(Error::new(ErrorKind::InvalidInput, format!("Couldn't guess the format of '{file_path}'!")))
+        }
+    }
+}
+
+impl Maze{
+    pub fn parse_from_lab_vector(data: Vec<u8>) -> Result<Self, Error>{
+        if data.len() < 4 {
+            return Err(Error::new(ErrorKind::InvalidData, "Maze header is incomplete!"))
+        }
+        let rows = u16::from_be_bytes([data[0], data[1]]) as usize;
+        let columns = u16::from_be_bytes([data[2], data[3]]) as usize;
+        if rows == 0 || columns == 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "Maze dimensions can't be zero!"))
+        }
+        Maze::parse_from_vector_with_dimensions(data[4..].to_vec(), (rows, columns))
+    }
+
+    pub fn to_txt_string(&self) -> String{
+        let mut output = String::new();
+        for row in self.fields.iter(){
+            for field in row.iter(){
+                let mut walls = 0;
+                let mut doors = 0;
+                for (d, direction) in DEFAULT_DIRECTIONS.iter().enumerate(){
+                    if !field.walls[d] {
+                        walls |= direction;
+                    }
+                    if field.doors[d] {
+                        doors |= direction;
+                    }
+                }
+                let mut info = 0;
+                if field.key {
+                    info |= DEFAULT_KEY;
+                }
+                if field.exit {
+                    info |= DEFAULT_END;
+                }
+                output += format!("{walls:04b} {doors:04b} {info:04b}\n").as_str();
+            }
+        }
+        output
+    }
+
+    pub fn to_vector(&self) -> Vec<u8>{
+        convert_string_to_u8(&self.to_txt_string())
+    }
+
+    pub fn to_lab_vector(&self) -> Vec<u8>{
+        let mut data = Vec::new();
+        data.extend_from_slice(&(self.dimensions.0 as u16).to_be_bytes());
+        data.extend_from_slice(&(self.dimensions.1 as u16).to_be_bytes());
+        data.extend(self.to_vector());
+        data
+    }
+
+    pub fn save_with_format(&self, file_path: &String, format: MazeFormat) -> Result<(), Error>{
+        let data = self.to_format_vector(format)?;
+        let mut file = File::create(file_path)?;
+        file.write_all(&data)
+    }
+
+    // Txt and bin have no room for the dimensions, so they only take mazes the parser will read back
+    pub fn to_format_vector(&self, format: MazeFormat) -> Result<Vec<u8>, Error>{
+        let has_default_dimensions = self.dimensions == (DEFAULT_ROWS, DEFAULT_COLUMNS);
+        Ok(match format {
+            MazeFormat::Txt if has_default_dimensions => self.to_txt_string().into_bytes(),
+            MazeFormat::Bin if has_default_dimensions => self.to_vector(),
+            MazeFormat::Lab => {
+                if self.dimensions.0 > u16::MAX as usize || self.dimensions.1 > u16::MAX as usize {
+                    return Err(Error::new(ErrorKind::InvalidInput, "Maze is too large for the lab format!"))
+                }
+                self.to_lab_vector()
+            },
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The {format:?} format only holds {DEFAULT_ROWS}x{DEFAULT_COLUMNS} mazes, use the lab format instead!")
+                ))
+            }
+        })
+    }
+}
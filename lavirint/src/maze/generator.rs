@@ -0,0 +1,147 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use super::{Maze, field::Field, state::State, DEFAULT_ROWS, DEFAULT_COLUMNS};
+
+#[derive(Clone, Debug)]
+pub struct GeneratorOptions{
+    pub seed: u64,
+    pub dimensions: (usize, usize),
+    pub doors: usize,
+    pub keys: usize,
+    pub exits: usize
+}
+
+impl Default for GeneratorOptions{
+    fn default() -> Self {
+        Self { seed: 0, dimensions: (DEFAULT_ROWS, DEFAULT_COLUMNS), doors: 2, keys: 2, exits: 1 }
+    }
+}
+
+fn opposite(direction: usize) -> usize{
+    direction ^ 1
+}
+
+impl Maze{
+    pub fn generate(options: &GeneratorOptions) -> Self{
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let (rows, columns) = (options.dimensions.0.max(1), options.dimensions.1.max(1));
+
+        let mut maze = Maze{ dimensions: (rows, columns), ..Default::default() };
+        for i in 0..rows{
+            maze.fields.push((0..columns).map(|j| Field{ position: (i, j), ..Default::default() }).collect());
+        }
+
+        let mut passages = Vec::new();
+        let mut visited = HashSet::from([maze.start]);
+        let mut stack = vec![maze.start];
+        while let Some(&current) = stack.last(){
+            let candidates: Vec<(usize, (usize, usize))> = (0..4)
+                .filter_map(|d| maze.get_neighbour(&current, d).map(|n| (d, n)))
+                .filter(|(_, n)| !visited.contains(n))
+                .collect();
+            match candidates.choose(&mut rng) {
+                Some(&(direction, next)) => {
+                    maze.fields[current.0][current.1].walls[direction] = false;
+                    maze.fields[next.0][next.1].walls[opposite(direction)] = false;
+                    passages.push((current, direction));
+                    visited.insert(next);
+                    stack.push(next);
+                },
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+
+        // Exits are picked among the border fields furthest from the start
+        let walls_graph = maze.get_walls_graph();
+        let mut distances = HashMap::from([(maze.start, 0)]);
+        let mut queue = VecDeque::from([maze.start]);
+        while let Some(current) = queue.pop_front(){
+            let distance = distances[&current];
+            for next in walls_graph.get(&current).unwrap(){
+                if !distances.contains_key(next) {
+                    distances.insert(*next, distance + 1);
+                    queue.push_back(*next);
+                }
+            }
+        }
+        let mut border: Vec<(usize, usize)> = (0..rows)
+            .flat_map(|i| (0..columns).map(move |j| (i, j)))
+            .filter(|&(i, j)| (i == 0 || j == 0 || i + 1 == rows || j + 1 == columns) && (i, j) != maze.start)
+            .collect();
+        if border.is_empty() {
+            border.push(maze.start);
+        }
+        border.sort_by_key(|position| std::cmp::Reverse(distances[position]));
+        border.truncate((border.len() / 2).max(options.exits.max(1)));
+        border.shuffle(&mut rng);
+        for &(i, j) in border.iter().take(options.exits.max(1)){
+            maze.fields[i][j].exit = true;
+            maze.exits.insert((i, j));
+        }
+
+        // Doors are one-sided like in the specification, they face away from the start
+        passages.shuffle(&mut rng);
+        for &(position, direction) in passages.iter().take(options.doors){
+            maze.fields[position.0][position.1].doors[direction] = true;
+        }
+
+        // Every door gets a key placed somewhere the player can already reach, so the maze stays solvable,
+        // a door with no free field in front of it is taken out again
+        let doors_graph = maze.get_doors_graph();
+        let mut opened = HashSet::new();
+        let mut keys_placed = 0;
+        loop {
+            let mut region = HashSet::from([maze.start]);
+            let mut queue = VecDeque::from([maze.start]);
+            let mut frontier = Vec::new();
+            while let Some(current) = queue.pop_front(){
+                for next in walls_graph.get(&current).unwrap(){
+                    if doors_graph.get(&current).unwrap().contains(next) && !opened.contains(&(current, *next)){
+                        frontier.push((current, *next));
+                    }else if region.insert(*next){
+                        queue.push_back(*next);
+                    }
+                }
+            }
+            frontier.retain(|(_, next)| !region.contains(next));
+            frontier.sort();
+            let door = match frontier.choose(&mut rng) {
+                Some(&door) => door,
+                None => break
+            };
+            let mut free: Vec<&(usize, usize)> = region.iter()
+                .filter(|&&(i, j)| !maze.fields[i][j].key && (i, j) != maze.start)
+                .collect();
+            free.sort();
+            match free.choose(&mut rng) {
+                Some(&&(i, j)) => {
+                    maze.fields[i][j].key = true;
+                    keys_placed += 1;
+                },
+                None => {
+                    let ((i, j), next) = door;
+                    let direction = (0..4).find(|&d| maze.get_neighbour(&(i, j), d) == Some(next)).unwrap();
+                    maze.fields[i][j].doors[direction] = false;
+                }
+            }
+            opened.insert(door);
+            opened.insert((door.1, door.0));
+        }
+
+        let mut free: Vec<(usize, usize)> = (0..rows)
+            .flat_map(|i| (0..columns).map(move |j| (i, j)))
+            .filter(|&(i, j)| !maze.fields[i][j].key && (i, j) != maze.start)
+            .collect();
+        free.shuffle(&mut rng);
+        for &(i, j) in free.iter().take(options.keys.saturating_sub(keys_placed)){
+            maze.fields[i][j].key = true;
+        }
+
+        maze.state = Some(State::create_from_maze(&maze));
+        maze
+    }
+}
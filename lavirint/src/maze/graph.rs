@@ -0,0 +1,302 @@
+use std::{collections::{HashMap, HashSet, VecDeque}, io::{Error, ErrorKind}, str::FromStr};
+
+use super::{Maze, state::State, layout::{FIELD_COLOR, WALL_COLOR, DOOR_COLOR, SOLUTION_FIELD_COLOR, KEY_ICON_COLOR, EXIT_ICON_COLOR, START_ICON_COLOR}};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphKind{
+    #[default]
+    Passages,
+    States,
+    Regions
+}
+
+impl FromStr for GraphKind{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "passages" => Ok(GraphKind::Passages),
+            "states" => Ok(GraphKind::States),
+            "regions" => Ok(GraphKind::Regions),
+            _ => Err(Error::new(ErrorKind::InvalidInput, format!("Unknown graph '{s}'!")))
+        }
+    }
+}
+
+// Every state reached by a breadth first search, in the order they were expanded
+#[derive(Clone, Debug, Default)]
+pub struct Exploration{
+    pub states: Vec<State>,
+    pub depths: Vec<usize>,
+    pub edges: Vec<(usize, usize)>
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Region{
+    pub fields: Vec<(usize, usize)>,
+    pub keys: usize,
+    pub start: bool,
+    pub exit: bool
+}
+
+fn sorted(set: Option<&HashSet<(usize, usize)>>) -> Vec<(usize, usize)>{
+    let mut list: Vec<(usize, usize)> = set.map(|set| set.iter().copied().collect()).unwrap_or_default();
+    list.sort();
+    list
+}
+
+fn plural(count: usize, word: &str) -> String{
+    format!("{count} {word}{}", if count == 1 { "" } else { "s" })
+}
+
+fn field_id(position: (usize, usize)) -> String{
+    format!("\"{},{}\"", position.0, position.1)
+}
+
+impl Maze{
+    pub fn explore(&self, state: State) -> Exploration{
+        let walls_graph = self.get_walls_graph();
+        let mut exploration = Exploration::default();
+        let mut seen: HashMap<State, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        seen.insert(state.clone(), 0);
+        exploration.states.push(state);
+        exploration.depths.push(0);
+        queue.push_back(0);
+
+        while let Some(current) = queue.pop_front(){
+            if self.exits.contains(&exploration.states[current].position) {
+                continue;
+            }
+            for node in sorted(walls_graph.get(&exploration.states[current].position)){
+                if let Some(new_state) = exploration.states[current].transfer_state(&node) {
+                    let index = match seen.get(&new_state) {
+                        Some(index) => *index,
+                        None => {
+                            let index = exploration.states.len();
+                            seen.insert(new_state.clone(), index);
+                            exploration.states.push(new_state);
+                            exploration.depths.push(exploration.depths[current] + 1);
+                            queue.push_back(index);
+                            index
+                        }
+                    };
+                    exploration.edges.push((current, index));
+                }
+            }
+        }
+
+        exploration
+    }
+
+    pub fn regions(&self) -> (Vec<Region>, HashMap<(usize, usize), usize>){
+        let walls_graph = self.get_walls_graph();
+        let doors_graph = self.get_doors_graph();
+        let blocked = |a: &(usize, usize), b: &(usize, usize)|
+            doors_graph.get(a).is_some_and(|doors| doors.contains(b)) || doors_graph.get(b).is_some_and(|doors| doors.contains(a));
+
+        // Passages are walked both ways, a wall on one side only still joins the fields
+        let mut adjacent: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+        for row in 0..self.dimensions.0{
+            for col in 0..self.dimensions.1{
+                for to in sorted(walls_graph.get(&(row, col))){
+                    if !blocked(&(row, col), &to) {
+                        adjacent.entry((row, col)).or_default().push(to);
+                        adjacent.entry(to).or_default().push((row, col));
+                    }
+                }
+            }
+        }
+
+        let mut regions: Vec<Region> = Vec::new();
+        let mut region_of = HashMap::new();
+        for row in 0..self.dimensions.0{
+            for col in 0..self.dimensions.1{
+                if region_of.contains_key(&(row, col)) {
+                    continue;
+                }
+                let index = regions.len();
+                let mut region = Region::default();
+                let mut queue = VecDeque::from([(row, col)]);
+                region_of.insert((row, col), index);
+                while let Some(position) = queue.pop_front(){
+                    region.fields.push(position);
+                    let field = &self.fields[position.0][position.1];
+                    region.keys += field.key as usize;
+                    region.exit |= field.exit;
+                    region.start |= position == self.start;
+                    for neighbour in adjacent.get(&position).into_iter().flatten(){
+                        if !region_of.contains_key(neighbour) {
+                            region_of.insert(*neighbour, index);
+                            queue.push_back(*neighbour);
+                        }
+                    }
+                }
+                regions.push(region);
+            }
+        }
+
+        (regions, region_of)
+    }
+
+    fn field_attributes(&self, position: (usize, usize)) -> String{
+        let field = &self.fields[position.0][position.1];
+        let mut label = format!("{},{}", position.0, position.1);
+        let color = if position == self.start {
+            label += "\\nstart";
+            START_ICON_COLOR
+        }else if field.exit {
+            label += "\\nexit";
+            EXIT_ICON_COLOR
+        }else if field.key {
+            label += "\\nkey";
+            KEY_ICON_COLOR
+        }else{
+            FIELD_COLOR
+        };
+        format!("label=\"{label}\", fillcolor=\"#{color}\", pos=\"{},{}!\"", position.1, self.dimensions.0 - 1 - position.0)
+    }
+
+    pub fn passages_to_dot(&self) -> String{
+        let walls_graph = self.get_walls_graph();
+        let doors_graph = self.get_doors_graph();
+        let mut dot = String::from("digraph passages {\n");
+        dot += "    node [shape=box, style=filled, fontsize=10];\n";
+        dot += &format!("    edge [color=\"#{WALL_COLOR}\", arrowhead=none];\n");
+
+        for row in 0..self.dimensions.0{
+            for col in 0..self.dimensions.1{
+                dot += &format!("    {} [{}];\n", field_id((row, col)), self.field_attributes((row, col)));
+            }
+        }
+
+        for row in 0..self.dimensions.0{
+            for col in 0..self.dimensions.1{
+                let from = (row, col);
+                let doors = sorted(doors_graph.get(&from));
+                for to in sorted(walls_graph.get(&from)){
+                    let back = walls_graph.get(&to).is_some_and(|passages| passages.contains(&from));
+                    if doors.contains(&to) {
+                        dot += &format!(
+                            "    {} -> {} [color=\"#{DOOR_COLOR}\", style=bold, penwidth=2, arrowhead=tee, label=\"door\"];\n",
+                            field_id(from), field_id(to)
+                        );
+                    }else if !back {
+                        dot += &format!("    {} -> {} [style=dashed, arrowhead=normal];\n", field_id(from), field_id(to));
+                    }else if from < to && !doors_graph.get(&to).is_some_and(|doors| doors.contains(&from)) {
+                        dot += &format!("    {} -> {};\n", field_id(from), field_id(to));
+                    }
+                }
+            }
+        }
+
+        dot += "}\n";
+        dot
+    }
+
+    pub fn states_to_dot(&self, exploration: &Exploration, path: Option<&[(usize, usize)]>) -> String{
+        let mut highlighted = HashSet::new();
+        if let (Some(path), Some(first)) = (path, exploration.states.first()) {
+            let lookup: HashMap<&State, usize> = exploration.states.iter().enumerate().map(|(index, state)| (state, index)).collect();
+            let mut current = first.clone();
+            let mut previous = 0;
+            highlighted.insert((0, 0));
+            for position in path.iter().skip(1){
+                match current.transfer_state(position) {
+                    Some(next) => current = next,
+                    None => break
+                }
+                match lookup.get(&current) {
+                    Some(index) => {
+                        highlighted.insert((previous, *index));
+                        highlighted.insert((*index, *index));
+                        previous = *index;
+                    },
+                    None => break
+                }
+            }
+        }
+
+        let mut dot = String::from("digraph states {\n");
+        dot += "    node [shape=ellipse, style=filled, fontsize=10];\n";
+        dot += &format!("    edge [color=\"#{WALL_COLOR}\"];\n");
+        for (index, state) in exploration.states.iter().enumerate(){
+            let color = if highlighted.contains(&(index, index)) {
+                SOLUTION_FIELD_COLOR
+            }else if self.exits.contains(&state.position) {
+                EXIT_ICON_COLOR
+            }else{
+                FIELD_COLOR
+            };
+            dot += &format!(
+                "    s{index} [label=\"{},{}\\nkeys: {}\", fillcolor=\"#{color}\"];\n",
+                state.position.0, state.position.1, state.keys
+            );
+        }
+        for (from, to) in exploration.edges.iter(){
+            if highlighted.contains(&(*from, *to)) {
+                dot += &format!("    s{from} -> s{to} [color=\"#{DOOR_COLOR}\", penwidth=3];\n");
+            }else{
+                dot += &format!("    s{from} -> s{to};\n");
+            }
+        }
+        dot += "}\n";
+        dot
+    }
+
+    pub fn regions_to_dot(&self) -> String{
+        let (regions, region_of) = self.regions();
+        let doors_graph = self.get_doors_graph();
+        let mut doors: HashMap<(usize, usize), usize> = HashMap::new();
+        for row in 0..self.dimensions.0{
+            for col in 0..self.dimensions.1{
+                for to in sorted(doors_graph.get(&(row, col))){
+                    let (a, b) = (region_of[&(row, col)], region_of[&to]);
+                    if a != b {
+                        *doors.entry((a, b)).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let mut dot = String::from("digraph regions {\n");
+        dot += "    node [shape=box, style=\"rounded,filled\", fontsize=10];\n";
+        for (index, region) in regions.iter().enumerate(){
+            let mut label = format!("region {index}\\n{}", plural(region.fields.len(), "field"));
+            if region.keys > 0 {
+                label += &format!("\\n{}", plural(region.keys, "key"));
+            }
+            let color = if region.start {
+                label += "\\nstart";
+                START_ICON_COLOR
+            }else if region.exit {
+                EXIT_ICON_COLOR
+            }else{
+                FIELD_COLOR
+            };
+            if region.exit {
+                label += "\\nexit";
+            }
+            dot += &format!("    r{index} [label=\"{label}\", fillcolor=\"#{color}\"];\n");
+        }
+        let mut edges: Vec<((usize, usize), usize)> = doors.into_iter().collect();
+        edges.sort();
+        for ((from, to), count) in edges{
+            dot += &format!("    r{from} -> r{to} [color=\"#{DOOR_COLOR}\", style=bold, label=\"{}\"];\n", plural(count, "door"));
+        }
+        dot += "}\n";
+        dot
+    }
+
+    pub fn to_dot(&self, kind: GraphKind, path: Option<&[(usize, usize)]>) -> String{
+        match kind {
+            GraphKind::Passages => self.passages_to_dot(),
+            GraphKind::States => {
+                let state = self.state.clone().unwrap_or_else(|| State::create_from_maze(self));
+                self.states_to_dot(&self.explore(state), path)
+            },
+            GraphKind::Regions => self.regions_to_dot()
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::{Maze, state::State, distance::DistanceField};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hint{
+    pub moves: usize,
+    pub next: Option<(usize, usize)>,
+    pub next_key: Option<(usize, usize)>
+}
+
+impl DistanceField{
+    // Following the field downhill from the state walks one of the shortest paths to an exit
+    pub fn hint(&self, maze: &Maze, state: &State) -> Option<Hint>{
+        let moves = self.moves_left(state)?;
+        let next = self.next_move(maze, state);
+
+        let mut next_key = None;
+        let mut current = state.clone();
+        while let Some(position) = self.next_move(maze, &current){
+            let after = current.transfer_state(&position).unwrap();
+            if after.keys_set.len() < current.keys_set.len() {
+                next_key = Some(position);
+                break;
+            }
+            current = after;
+        }
+
+        Some(Hint{ moves, next, next_key })
+    }
+}
@@ -0,0 +1,119 @@
+use super::Maze;
+
+pub const FIELD_COLOR: &str = "DBCBEA";
+pub const WALL_COLOR: &str = "40315D";
+pub const DOOR_COLOR: &str = "248BB1";
+pub const SOLUTION_FIELD_COLOR: &str = "9DD6EA";
+pub const KEY_ICON_COLOR: &str = "C9A227";
+pub const EXIT_ICON_COLOR: &str = "2E8B57";
+pub const START_ICON_COLOR: &str = "D9534F";
+pub const PLAYER_COLOR: &str = "F08A24";
+
+pub const WALL_SCALE: f32 = 0.025;
+pub const DOOR_SCALE: f32 = 0.05;
+pub const KEY_SCALE: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Piece{
+    Wall,
+    Door
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect{
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32
+}
+
+// Same proportions as the Bevy view, but with the origin in the top left corner and y growing downwards
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Layout{
+    pub field: (f32, f32),
+    pub wall_size: f32,
+    pub door_size: f32,
+    pub key_size: (f32, f32)
+}
+
+pub fn hex_to_rgb(hex: &str) -> [u8; 3]{
+    let channel = |index: usize| hex.get(index..index + 2).and_then(|c| u8::from_str_radix(c, 16).ok()).unwrap_or(0);
+    [channel(0), channel(2), channel(4)]
+}
+
+impl Layout{
+    pub fn new(w: f32, h: f32) -> Self{
+        Self{
+            field: (w, h),
+            wall_size: w.min(h) * WALL_SCALE,
+            door_size: w.min(h) * DOOR_SCALE,
+            key_size: (w * KEY_SCALE, h * KEY_SCALE)
+        }
+    }
+
+    pub fn fit(maze: &Maze, width: f32, height: f32) -> Self{
+        Self::new(width / maze.dimensions.1 as f32, height / maze.dimensions.0 as f32)
+    }
+
+    pub fn size(&self, maze: &Maze) -> (f32, f32){
+        (self.field.0 * maze.dimensions.1 as f32, self.field.1 * maze.dimensions.0 as f32)
+    }
+
+    pub fn field_rect(&self, position: (usize, usize)) -> Rect{
+        let (w, h) = self.field;
+        Rect{ x: position.1 as f32 * w, y: position.0 as f32 * h, w, h }
+    }
+
+    pub fn field_center(&self, position: (usize, usize)) -> (f32, f32){
+        let (w, h) = self.field;
+        ((position.1 as f32 + 0.5) * w, (position.0 as f32 + 0.5) * h)
+    }
+
+    pub fn key_rect(&self, position: (usize, usize)) -> Rect{
+        let (x, y) = self.field_center(position);
+        let (w, h) = self.key_size;
+        Rect{ x: x - w / 2., y: y - h / 2., w, h }
+    }
+
+    pub fn wall_rect(&self, position: (usize, usize), direction: usize) -> Rect{
+        let field = self.field_rect(position);
+        let size = self.wall_size;
+        match direction {
+            0 => Rect{ w: size, ..field },
+            1 => Rect{ x: field.x + field.w - size, w: size, ..field },
+            2 => Rect{ h: size, ..field },
+            _ => Rect{ y: field.y + field.h - size, h: size, ..field }
+        }
+    }
+
+    pub fn door_rect(&self, position: (usize, usize), direction: usize) -> Rect{
+        let field = self.field_rect(position);
+        let size = self.door_size;
+        match direction {
+            0 => Rect{ x: field.x - size / 2., w: size, ..field },
+            1 => Rect{ x: field.x + field.w - size / 2., w: size, ..field },
+            2 => Rect{ y: field.y - size / 2., h: size, ..field },
+            _ => Rect{ y: field.y + field.h - size / 2., h: size, ..field }
+        }
+    }
+
+    // Doors first, walls are drawn over them
+    pub fn pieces(&self, maze: &Maze) -> Vec<(Piece, (usize, usize), Rect)>{
+        let mut doors = Vec::new();
+        let mut walls = Vec::new();
+        for (y, row) in maze.fields.iter().enumerate(){
+            for (x, field) in row.iter().enumerate(){
+                for direction in 0..4{
+                    if field.doors[direction] {
+                        doors.push((Piece::Door, (y, x), self.door_rect((y, x), direction)));
+                    }
+                    if field.walls[direction] {
+                        walls.push((Piece::Wall, (y, x), self.wall_rect((y, x), direction)));
+                    }
+                }
+            }
+        }
+        doors.append(&mut walls);
+        doors
+    }
+}
@@ -1,19 +1,33 @@
 pub mod field;
 pub mod state;
+pub mod difficulty;
+pub mod format;
+pub mod solver;
+pub mod generator;
+pub mod validation;
+pub mod render;
+pub mod layout;
+pub mod svg;
+pub mod raster;
+pub mod graph;
+pub mod search;
+pub mod hint;
+pub mod distance;
+pub mod visibility;
 
 use std::{fmt::Display, io::{Error, ErrorKind}, collections::{HashMap, HashSet, VecDeque}, thread::spawn, sync::mpsc, time::Instant};
 
-use crate::utilities::read_binary;
+use crate::utilities::{read_binary, read_text, convert_string_to_u8};
 
-use self::{field::Field, state::State};
+use self::{field::Field, state::State, format::MazeFormat, render::RenderOptions};
 
-const DEFAULT_ROWS: usize = 6;
-const DEFAULT_COLUMNS: usize = 9;
+pub const DEFAULT_ROWS: usize = 6;
+pub const DEFAULT_COLUMNS: usize = 9;
 
 const DEFAULT_END: u8 = 0b11;
 const DEFAULT_KEY: u8 = 0b1100;
 
-const DEFAULT_DIRECTIONS: &'static [u8; 4] = &[0b1000, 0b100, 0b10, 0b1];
+const DEFAULT_DIRECTIONS: &[u8; 4] = &[0b1000, 0b100, 0b10, 0b1];
 
 #[derive(Clone)]
 pub struct Maze {
@@ -38,17 +52,10 @@ impl Default for Maze{
 
 impl Display for Maze{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut output = format!("Dimensions: ({}, {})\n", self.dimensions.0, self.dimensions.1);
-        output += format!("Exit count: ({})\n", self.exits.len()).as_str();
-        output += "Field data:";
-        for (row_index, row) in self.fields.iter().enumerate(){
-            output += format!("\nRow[{row_index}]: ").as_str();
-            for (_, element) in row.iter().enumerate(){
-                output += format!("{}", element).as_str();
-            }
-            output += "\n";
+        if self.fields.is_empty() {
+            return write!(f, "Empty maze ({}, {})", self.dimensions.0, self.dimensions.1);
         }
-        f.write_str(&output)
+        f.write_str(&self.render(&RenderOptions::default()))
     }
 }
 
@@ -60,8 +67,29 @@ impl Maze{
         }
     }
 
+    pub fn parse_from_txt_file(txt_file_path: &String) -> Result<Self, Error>{
+        let content = read_text(txt_file_path)?;
+        Maze::parse_from_vector(convert_string_to_u8(&content))
+    }
+
+    pub fn parse_from_path(file_path: &String) -> Result<Self, Error>{
+        Maze::parse_with_format(file_path, MazeFormat::from_path(file_path)?)
+    }
+
+    pub fn parse_with_format(file_path: &String, format: MazeFormat) -> Result<Self, Error>{
+        match format {
+            MazeFormat::Txt => Maze::parse_from_txt_file(file_path),
+            MazeFormat::Bin => Maze::parse_from_file(file_path),
+            MazeFormat::Lab => Maze::parse_from_lab_vector(read_binary(file_path)?)
+        }
+    }
+
     pub fn parse_from_vector(data: Vec<u8>) -> Result<Self, Error>{
-        let mut maze = Maze::default();
+        Maze::parse_from_vector_with_dimensions(data, (DEFAULT_ROWS, DEFAULT_COLUMNS))
+    }
+
+    pub fn parse_from_vector_with_dimensions(data: Vec<u8>, dimensions: (usize, usize)) -> Result<Self, Error>{
+        let mut maze = Maze{ dimensions, ..Default::default() };
         if (data.len() as f32) < maze.dimensions.0 as f32 * maze.dimensions.1 as f32 * 1.5 {
             return Err(Error::new(ErrorKind::InvalidData, "Maze data is incomplete!"))
         }
@@ -76,8 +104,7 @@ impl Maze{
         for i in 0..maze.dimensions.0{
             maze.fields.push(Vec::<Field>::new());
             for j in 0..maze.dimensions.1{
-                let mut field = Field::default();
-                field.position = (i, j);
+                let mut field = Field{ position: (i, j), ..Default::default() };
                 if new_row{
                     walls = data[index] >> 4;
                     doors = data[index] & 0b1111;
@@ -93,9 +120,9 @@ impl Maze{
                     index += 2;
                     new_row = true;
                 }
-                for j in 0..4{
-                    field.walls[j] = (walls & DEFAULT_DIRECTIONS[j]) != DEFAULT_DIRECTIONS[j];
-                    field.doors[j] = (doors & DEFAULT_DIRECTIONS[j]) == DEFAULT_DIRECTIONS[j];
+                for (d, direction) in DEFAULT_DIRECTIONS.iter().enumerate(){
+                    field.walls[d] = (walls & direction) != *direction;
+                    field.doors[d] = (doors & direction) == *direction;
                 }
                 if (key & DEFAULT_KEY) == DEFAULT_KEY{
                     field.key = true;
@@ -108,7 +135,7 @@ impl Maze{
             }
         }
 
-        if maze.exits.len() < 1 {
+        if maze.exits.is_empty() {
             Err(Error::new(ErrorKind::InvalidInput, "Maze doesn't have an exit!"))
         }else{
             maze.state = Some(State::create_from_maze(&maze));
@@ -132,15 +159,15 @@ impl Maze{
                                 3 if row_index + 1 < self.dimensions.0 => Some((row_index + 1, col_index)),
                                 _ => None
                         };
-                        if neighbour.is_some(){
-                            ret.get_mut(&(row_index, col_index)).unwrap().insert(neighbour.unwrap());
+                        if let Some(neighbour) = neighbour{
+                            ret.get_mut(&(row_index, col_index)).unwrap().insert(neighbour);
                         }
                     }
                 }
             }
         }
 
-        return ret;
+        ret
     }
 
     fn get_walls_graph(&self)->HashMap<(usize,usize), HashSet<(usize,usize)>>{
@@ -159,15 +186,15 @@ impl Maze{
                             3 if row_index + 1 < self.dimensions.0 => Some((row_index + 1, col_index)),
                             _ => None
                         };
-                        if neighbour.is_some(){
-                            ret.get_mut(&(row_index, col_index)).unwrap().insert(neighbour.unwrap());
+                        if let Some(neighbour) = neighbour{
+                            ret.get_mut(&(row_index, col_index)).unwrap().insert(neighbour);
                         }
                     }
                 }
             }
         }
 
-        return ret;
+        ret
     }
 
     #[allow(dead_code)]
@@ -182,8 +209,8 @@ impl Maze{
                     3 if position.0 + 1 < self.dimensions.0 => Some((position.0 + 1, position.1)),
                     _ => None
                 };
-                if res.is_some(){
-                    ret.insert(res.unwrap());
+                if let Some(res) = res{
+                    ret.insert(res);
                 }
             }
         }
@@ -202,14 +229,25 @@ impl Maze{
                     3 if position.0 + 1 < self.dimensions.0 => Some((position.0 + 1, position.1)),
                     _ => None
                 };
-                if res.is_some(){
-                    ret.insert(res.unwrap());
+                if let Some(res) = res{
+                    ret.insert(res);
                 }
             }
         }
         ret
     }
 
+    // Field across the given side, directions go left, right, up, down like in Field::walls
+    pub fn get_neighbour(&self, position: &(usize, usize), direction: usize) -> Option<(usize, usize)>{
+        match direction {
+            0 if position.1 > 0 => Some((position.0, position.1 - 1)),
+            1 if position.1 + 1 < self.dimensions.1 => Some((position.0, position.1 + 1)),
+            2 if position.0 > 0 => Some((position.0 - 1, position.1)),
+            3 if position.0 + 1 < self.dimensions.0 => Some((position.0 + 1, position.1)),
+            _ => None
+        }
+    }
+
     pub fn get_keys_set(&self) -> HashSet<(usize, usize)>{
         let mut ret = HashSet::new();
 
@@ -221,57 +259,21 @@ impl Maze{
             }
         }
 
-        return ret;
+        ret
     }
 
     pub fn search_for_shortest_path(&self, state: State) -> Option<Vec<(usize, usize)>>{
-        if self.exits.contains(&state.position){ return Some(vec![state.position]); }
-
-        let walls_graph = self.get_walls_graph();
-
-        let mut state_history = Vec::new();
-        state_history.push(state);
-
-        let mut queue = VecDeque::new();
-        queue.push_back(state_history);
-
-        while !queue.is_empty(){
-            let current_history = queue.pop_front().unwrap();
-            let current_state = current_history.last().unwrap();
-            let current_position = current_state.position;
-
-            let neighbours = walls_graph.get(&current_position).unwrap();
-
-            for node in neighbours{
-                let potential_new_state = current_state.transfer_state(node);
-                if let Some(new_state) = potential_new_state{
-                    if !current_history.contains(&new_state){
-                        let mut new_history = current_history.clone();
-                        new_history.push(new_state);
-                        if self.exits.contains(node){
-                            return Some(
-                                new_history.iter()
-                                            .map(|state|{ state.position })
-                                            .collect()
-                                )
-                        }
-                        queue.push_back(new_history);
-                    }
-                }
-            }
-        }
-        None
+        self.search_for_shortest_path_traced(state).path
     }
 
     pub fn search_for_shortest_path_parallel(&self, state: State) -> Option<Vec<(usize, usize)>> {
-        let start_position = state.position.clone();
+        let start_position = state.position;
 
         if self.exits.contains(&start_position){ return Some(vec![start_position]); }
 
         let walls_graph = self.get_walls_graph();
 
-        let mut state_history = Vec::new();
-        state_history.push(state);
+        let state_history = vec![state];
 
         let mut queue = VecDeque::new();
         queue.push_back(state_history);
@@ -290,7 +292,7 @@ impl Maze{
 
             for node in neighbours{
                 let mut new_history = current_history.clone();
-                let node_copy = node.clone();
+                let node_copy = *node;
                 let exists = self.exits.clone();
                 let solutions = solutions_tx.clone();
                 let states = states_tx.clone();
@@ -317,7 +319,7 @@ impl Maze{
                 t.join().unwrap();
             }
 
-            for solution in solutions_rx.try_iter(){
+            if let Some(solution) = solutions_rx.try_iter().next(){
                 return Some(solution);
             }
 
@@ -341,7 +343,7 @@ impl Maze{
         println!("Parallel time taken: {}", now.elapsed().as_secs_f64());
     }
 
-    pub fn get_state_mut<'a>(&'a mut self) -> &'a mut State{
+    pub fn get_state_mut(&mut self) -> &mut State{
         if self.state.is_none(){
             self.state = Some(State::create_from_maze(self));
         }
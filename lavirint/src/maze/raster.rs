@@ -0,0 +1,174 @@
+use std::{fs, io::{Error, ErrorKind}};
+
+use super::layout::{hex_to_rgb, Layout, Piece, Rect, FIELD_COLOR, WALL_COLOR, DOOR_COLOR, SOLUTION_FIELD_COLOR, KEY_ICON_COLOR, EXIT_ICON_COLOR, START_ICON_COLOR, PLAYER_COLOR};
+use super::Maze;
+
+const BACKGROUND_COLOR: &str = "FFFFFF";
+
+#[derive(Clone)]
+pub struct RasterOptions<'a>{
+    pub cell_size: u32,
+    pub player: Option<(usize, usize)>,
+    pub path: Option<&'a [(usize, usize)]>
+}
+
+impl Default for RasterOptions<'_>{
+    fn default() -> Self {
+        Self{ cell_size: 32, player: None, path: None }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image{
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>
+}
+
+impl Image{
+    pub fn new(width: u32, height: u32, color: [u8; 3]) -> Self{
+        Self{ width, height, pixels: color.repeat((width * height) as usize) }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3]{
+        let index = ((y * self.width + x) * 3) as usize;
+        [self.pixels[index], self.pixels[index + 1], self.pixels[index + 2]]
+    }
+
+    fn span(start: f32, length: f32, limit: u32) -> (u32, u32){
+        let from = start.round().max(0.) as u32;
+        let to = ((start + length).round().max(0.) as u32).max(from + 1);
+        (from.min(limit), to.min(limit))
+    }
+
+    pub fn fill_rect(&mut self, rect: &Rect, color: [u8; 3]){
+        let (x0, x1) = Self::span(rect.x, rect.w, self.width);
+        let (y0, y1) = Self::span(rect.y, rect.h, self.height);
+        for y in y0..y1{
+            let row = (y * self.width) as usize * 3;
+            for x in x0..x1{
+                let index = row + x as usize * 3;
+                self.pixels[index..index + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    pub fn fill_circle(&mut self, center: (f32, f32), radius: f32, color: [u8; 3]){
+        let (x0, x1) = Self::span(center.0 - radius, 2. * radius, self.width);
+        let (y0, y1) = Self::span(center.1 - radius, 2. * radius, self.height);
+        for y in y0..y1{
+            for x in x0..x1{
+                let (dx, dy) = (x as f32 + 0.5 - center.0, y as f32 + 0.5 - center.1);
+                if dx * dx + dy * dy <= radius * radius {
+                    let index = ((y * self.width + x) * 3) as usize;
+                    self.pixels[index..index + 3].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, Error>{
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(Error::other)?;
+        writer.write_image_data(&self.pixels).map_err(Error::other)?;
+        writer.finish().map_err(Error::other)?;
+        Ok(data)
+    }
+
+    pub fn from_png(data: &[u8]) -> Result<Self, Error>{
+        let mut decoder = png::Decoder::new(data);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info().map_err(|er| Error::new(ErrorKind::InvalidData, er))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(|er| Error::new(ErrorKind::InvalidData, er))?;
+        let channels = info.color_type.samples();
+        if channels < 3 {
+            return Err(Error::new(ErrorKind::InvalidData, "Only RGB images are supported!"));
+        }
+        let pixels = buffer[..info.buffer_size()]
+            .chunks(channels)
+            .flat_map(|pixel| pixel[..3].to_vec())
+            .collect();
+        Ok(Self{ width: info.width, height: info.height, pixels })
+    }
+
+    pub fn save_png(&self, file_path: &str) -> Result<(), Error>{
+        fs::write(file_path, self.to_png()?)
+    }
+}
+
+fn key_icon(image: &mut Image, area: &Rect){
+    let color = hex_to_rgb(KEY_ICON_COLOR);
+    let (cx, cy) = (area.x + area.w / 2., area.y + area.h / 2.);
+    let stroke = area.h * 0.12;
+    image.fill_circle((cx - area.w * 0.25, cy), area.h * 0.22, color);
+    image.fill_circle((cx - area.w * 0.25, cy), area.h * 0.1, hex_to_rgb(FIELD_COLOR));
+    let shaft = Rect{ x: cx - area.w * 0.1, y: cy - stroke / 2., w: area.w * 0.55, h: stroke };
+    image.fill_rect(&shaft, color);
+    image.fill_rect(&Rect{ x: shaft.x + shaft.w - stroke, y: cy, w: stroke, h: area.h * 0.2 }, color);
+}
+
+fn exit_icon(image: &mut Image, area: &Rect){
+    let color = hex_to_rgb(EXIT_ICON_COLOR);
+    let stroke = area.h * 0.1;
+    let door = Rect{ x: area.x + area.w * 0.2, w: area.w * 0.6, ..*area };
+    image.fill_rect(&door, color);
+    image.fill_rect(&Rect{ x: door.x + stroke, y: door.y + stroke, w: door.w - 2. * stroke, h: door.h - stroke }, hex_to_rgb(FIELD_COLOR));
+    image.fill_circle((door.x + door.w * 0.7, door.y + door.h * 0.55), stroke * 0.7, color);
+}
+
+impl Maze{
+    pub fn rasterize(&self, options: &RasterOptions) -> Image{
+        let cell_size = options.cell_size.max(1) as f32;
+        let layout = Layout::new(cell_size, cell_size);
+        let (width, height) = layout.size(self);
+        let margin = layout.door_size.ceil();
+        let mut image = Image::new(
+            (width + 2. * margin) as u32,
+            (height + 2. * margin) as u32,
+            hex_to_rgb(BACKGROUND_COLOR)
+        );
+        let shift = |rect: Rect| Rect{ x: rect.x + margin, y: rect.y + margin, ..rect };
+        let center = |position: (usize, usize)| {
+            let (x, y) = layout.field_center(position);
+            (x + margin, y + margin)
+        };
+
+        image.fill_rect(&shift(Rect{ x: 0., y: 0., w: width, h: height }), hex_to_rgb(FIELD_COLOR));
+        if let Some(path) = options.path {
+            for position in path.iter(){
+                image.fill_rect(&shift(layout.field_rect(*position)), hex_to_rgb(SOLUTION_FIELD_COLOR));
+            }
+        }
+
+        for (y, row) in self.fields.iter().enumerate(){
+            for (x, field) in row.iter().enumerate(){
+                if field.exit {
+                    exit_icon(&mut image, &shift(layout.key_rect((y, x))));
+                }
+                if field.key {
+                    key_icon(&mut image, &shift(layout.key_rect((y, x))));
+                }
+            }
+        }
+        image.fill_circle(center(self.start), cell_size * 0.12, hex_to_rgb(START_ICON_COLOR));
+
+        for (piece, _, area) in layout.pieces(self){
+            let color = if piece == Piece::Door { DOOR_COLOR } else { WALL_COLOR };
+            image.fill_rect(&shift(area), hex_to_rgb(color));
+        }
+
+        if let Some(player) = options.player {
+            image.fill_circle(center(player), cell_size * 0.3, hex_to_rgb(PLAYER_COLOR));
+        }
+
+        image
+    }
+
+    pub fn save_png(&self, file_path: &str, options: &RasterOptions) -> Result<(), Error>{
+        self.rasterize(options).save_png(file_path)
+    }
+}
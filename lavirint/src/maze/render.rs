@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use super::{Maze, state::State};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Charset{
+    #[default]
+    Ascii,
+    Unicode
+}
+
+#[derive(Clone, Default)]
+pub struct RenderOptions<'a>{
+    pub charset: Charset,
+    pub state: Option<&'a State>,
+    pub player: Option<(usize, usize)>,
+    pub path: Option<&'a [(usize, usize)]>
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Edge{
+    Open,
+    Wall,
+    Door
+}
+
+#[derive(Clone, Copy, Default)]
+struct PathMark{
+    arrow: Option<usize>,
+    key_pickup: bool
+}
+
+pub const LEGEND: &str = "S start, E exit, k key, * key picked up on the path, @ player, = and : doors";
+
+const CELL_WIDTH: usize = 3;
+
+fn arrow(charset: Charset, direction: usize) -> char{
+    match (charset, direction) {
+        (Charset::Ascii, 0) => '<',
+        (Charset::Ascii, 1) => '>',
+        (Charset::Ascii, 2) => '^',
+        (Charset::Ascii, _) => 'v',
+        (Charset::Unicode, 0) => '←',
+        (Charset::Unicode, 1) => '→',
+        (Charset::Unicode, 2) => '↑',
+        (Charset::Unicode, _) => '↓'
+    }
+}
+
+fn junction(charset: Charset, up: bool, down: bool, left: bool, right: bool) -> char{
+    match charset {
+        Charset::Ascii => match (up || down, left || right) {
+            (false, false) => ' ',
+            (true, false) => '|',
+            (false, true) => '-',
+            (true, true) => '+'
+        },
+        Charset::Unicode => match (up, down, left, right) {
+            (false, false, false, false) => ' ',
+            (true, false, false, false) => '╵',
+            (false, true, false, false) => '╷',
+            (false, false, true, false) => '╴',
+            (false, false, false, true) => '╶',
+            (true, true, false, false) => '│',
+            (false, false, true, true) => '─',
+            (false, true, false, true) => '┌',
+            (false, true, true, false) => '┐',
+            (true, false, false, true) => '└',
+            (true, false, true, false) => '┘',
+            (true, true, false, true) => '├',
+            (true, true, true, false) => '┤',
+            (false, true, true, true) => '┬',
+            (true, false, true, true) => '┴',
+            (true, true, true, true) => '┼'
+        }
+    }
+}
+
+fn horizontal_glyph(charset: Charset, edge: Edge) -> char{
+    match (charset, edge) {
+        (_, Edge::Open) => ' ',
+        (Charset::Ascii, Edge::Wall) => '-',
+        (Charset::Ascii, Edge::Door) => '=',
+        (Charset::Unicode, Edge::Wall) => '─',
+        (Charset::Unicode, Edge::Door) => '═'
+    }
+}
+
+fn vertical_glyph(charset: Charset, edge: Edge) -> char{
+    match (charset, edge) {
+        (_, Edge::Open) => ' ',
+        (Charset::Ascii, Edge::Wall) => '|',
+        (Charset::Ascii, Edge::Door) => ':',
+        (Charset::Unicode, Edge::Wall) => '│',
+        (Charset::Unicode, Edge::Door) => '║'
+    }
+}
+
+fn direction_between(from: (usize, usize), to: (usize, usize)) -> usize{
+    if to.1 < from.1 {
+        0
+    }else if to.1 > from.1 {
+        1
+    }else if to.0 < from.0 {
+        2
+    }else{
+        3
+    }
+}
+
+impl Maze{
+    fn edge_between(&self, state: &State, a: (usize, usize), b: (usize, usize)) -> Edge{
+        let direction = direction_between(a, b);
+        if self.fields[a.0][a.1].walls[direction] || self.fields[b.0][b.1].walls[direction ^ 1] {
+            Edge::Wall
+        }else if state.doors_graph.get(&a).is_some_and(|doors| doors.contains(&b))
+            || state.doors_graph.get(&b).is_some_and(|doors| doors.contains(&a)) {
+            Edge::Door
+        }else{
+            Edge::Open
+        }
+    }
+
+    // Edge below the row, -1 stands for the top border
+    fn horizontal_edge(&self, state: &State, row: isize, col: usize) -> Edge{
+        let border = |position: (usize, usize), direction: usize|
+            if self.fields[position.0][position.1].walls[direction] { Edge::Wall } else { Edge::Open };
+        if row < 0 {
+            border((0, col), 2)
+        }else if row as usize + 1 >= self.dimensions.0 {
+            border((row as usize, col), 3)
+        }else{
+            self.edge_between(state, (row as usize, col), (row as usize + 1, col))
+        }
+    }
+
+    // Edge right of the column, -1 stands for the left border
+    fn vertical_edge(&self, state: &State, row: usize, col: isize) -> Edge{
+        let border = |position: (usize, usize), direction: usize|
+            if self.fields[position.0][position.1].walls[direction] { Edge::Wall } else { Edge::Open };
+        if col < 0 {
+            border((row, 0), 0)
+        }else if col as usize + 1 >= self.dimensions.1 {
+            border((row, col as usize), 1)
+        }else{
+            self.edge_between(state, (row, col as usize), (row, col as usize + 1))
+        }
+    }
+
+    fn path_marks(&self, state: &State, path: &[(usize, usize)]) -> HashMap<(usize, usize), PathMark>{
+        let mut marks: HashMap<(usize, usize), PathMark> = HashMap::new();
+        let mut current = state.clone();
+        for (index, position) in path.iter().enumerate(){
+            if index > 0 {
+                if let Some(next) = current.transfer_state(position) {
+                    if next.keys_set.len() < current.keys_set.len() {
+                        marks.entry(*position).or_default().key_pickup = true;
+                    }
+                    current = next;
+                }
+            }
+            if let Some(next) = path.get(index + 1) {
+                marks.entry(*position).or_default().arrow = Some(direction_between(*position, *next));
+            }
+        }
+        marks
+    }
+
+    fn cell_glyphs(&self, state: &State, options: &RenderOptions, position: (usize, usize), mark: Option<&PathMark>) -> String{
+        let field = &self.fields[position.0][position.1];
+        let arrow = mark.and_then(|mark| mark.arrow).map(|direction| arrow(options.charset, direction));
+        let symbol = if options.player == Some(position) {
+            Some('@')
+        }else if mark.is_some_and(|mark| mark.key_pickup) {
+            Some('*')
+        }else if position == self.start {
+            Some('S')
+        }else if field.exit {
+            Some('E')
+        }else if state.keys_set.contains(&position) {
+            Some('k')
+        }else{
+            None
+        };
+        match (symbol, arrow) {
+            (Some(symbol), Some(arrow)) => format!(" {symbol}{arrow}"),
+            (Some(symbol), None) => format!(" {symbol} "),
+            (None, Some(arrow)) => format!(" {arrow} "),
+            (None, None) => " ".repeat(CELL_WIDTH)
+        }
+    }
+
+    pub fn render_lines(&self, options: &RenderOptions) -> Vec<String>{
+        let initial_state;
+        let state = match options.state.or(self.state.as_ref()) {
+            Some(state) => state,
+            None => {
+                initial_state = State::create_from_maze(self);
+                &initial_state
+            }
+        };
+        let marks = match options.path {
+            Some(path) => self.path_marks(state, path),
+            None => HashMap::new()
+        };
+        let charset = options.charset;
+        let (rows, columns) = (self.dimensions.0 as isize, self.dimensions.1 as isize);
+        let mut lines = Vec::new();
+
+        for row in -1..rows{
+            let mut line = String::new();
+            for col in -1..columns{
+                let up = row >= 0 && self.vertical_edge(state, row as usize, col) != Edge::Open;
+                let down = row + 1 < rows && self.vertical_edge(state, (row + 1) as usize, col) != Edge::Open;
+                let left = col >= 0 && self.horizontal_edge(state, row, col as usize) != Edge::Open;
+                let right = col + 1 < columns && self.horizontal_edge(state, row, (col + 1) as usize) != Edge::Open;
+                line.push(junction(charset, up, down, left, right));
+                if col + 1 < columns {
+                    let glyph = horizontal_glyph(charset, self.horizontal_edge(state, row, (col + 1) as usize));
+                    line.extend(std::iter::repeat_n(glyph, CELL_WIDTH));
+                }
+            }
+            lines.push(line);
+
+            if row + 1 < rows {
+                let r = (row + 1) as usize;
+                let mut line = String::new();
+                for col in -1..columns{
+                    line.push(vertical_glyph(charset, self.vertical_edge(state, r, col)));
+                    if col + 1 < columns {
+                        let position = (r, (col + 1) as usize);
+                        line += self.cell_glyphs(state, options, position, marks.get(&position)).as_str();
+                    }
+                }
+                lines.push(line);
+            }
+        }
+
+        lines
+    }
+
+    pub fn render(&self, options: &RenderOptions) -> String{
+        self.render_lines(options).join("\n")
+    }
+}
@@ -0,0 +1,149 @@
+use std::{cmp::Reverse, collections::{BinaryHeap, HashSet, VecDeque}};
+
+use super::{Maze, state::State};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Expansion{
+    pub position: (usize, usize),
+    pub depth: usize
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchTrace{
+    pub expansions: Vec<Expansion>,
+    pub path: Option<Vec<(usize, usize)>>
+}
+
+pub type Signature = ((usize, usize), usize, Vec<(usize, usize)>, Vec<((usize, usize), (usize, usize))>);
+
+// Position and keys aren't enough to tell two states apart once a search can skip revisiting them
+pub fn signature(state: &State) -> Signature{
+    let mut keys: Vec<(usize, usize)> = state.keys_set.iter().copied().collect();
+    keys.sort();
+    let mut doors: Vec<((usize, usize), (usize, usize))> = state.doors_graph.iter()
+        .flat_map(|(from, to)| to.iter().map(move |to| (*from, *to)))
+        .collect();
+    doors.sort();
+    (state.position, state.keys, keys, doors)
+}
+
+impl Maze{
+    fn distance_to_exit(&self, position: (usize, usize)) -> usize{
+        self.exits.iter()
+            .map(|exit| exit.0.abs_diff(position.0) + exit.1.abs_diff(position.1))
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn search_for_shortest_path_traced(&self, state: State) -> SearchTrace{
+        let mut trace = SearchTrace::default();
+        if self.exits.contains(&state.position){
+            trace.expansions.push(Expansion{ position: state.position, depth: 0 });
+            trace.path = Some(vec![state.position]);
+            return trace;
+        }
+
+        let walls_graph = self.get_walls_graph();
+        let mut queue = VecDeque::new();
+        queue.push_back(vec![state]);
+
+        while let Some(current_history) = queue.pop_front(){
+            let current_state = current_history.last().unwrap();
+            trace.expansions.push(Expansion{ position: current_state.position, depth: current_history.len() - 1 });
+
+            for node in walls_graph.get(&current_state.position).unwrap(){
+                if let Some(new_state) = current_state.transfer_state(node){
+                    if !current_history.contains(&new_state){
+                        let mut new_history = current_history.clone();
+                        new_history.push(new_state);
+                        if self.exits.contains(node){
+                            trace.path = Some(new_history.iter().map(|state| state.position).collect());
+                            return trace;
+                        }
+                        queue.push_back(new_history);
+                    }
+                }
+            }
+        }
+        trace
+    }
+
+    pub fn search_a_star(&self, state: State) -> SearchTrace{
+        let walls_graph = self.get_walls_graph();
+        let mut trace = SearchTrace::default();
+        let mut nodes: Vec<(State, usize, Option<usize>)> = vec![(state, 0, None)];
+        let mut closed = HashSet::new();
+        let mut open = BinaryHeap::new();
+        open.push(Reverse((self.distance_to_exit(nodes[0].0.position), 0)));
+
+        while let Some(Reverse((_, index))) = open.pop(){
+            let (current_state, moves, _) = nodes[index].clone();
+            if !closed.insert(signature(&current_state)) {
+                continue;
+            }
+            trace.expansions.push(Expansion{ position: current_state.position, depth: moves });
+
+            if self.exits.contains(&current_state.position) {
+                let mut path = Vec::new();
+                let mut current = Some(index);
+                while let Some(node) = current {
+                    path.push(nodes[node].0.position);
+                    current = nodes[node].2;
+                }
+                path.reverse();
+                trace.path = Some(path);
+                return trace;
+            }
+
+            let mut neighbours: Vec<&(usize, usize)> = walls_graph.get(&current_state.position).unwrap().iter().collect();
+            neighbours.sort();
+            for node in neighbours{
+                if let Some(new_state) = current_state.transfer_state(node){
+                    if !closed.contains(&signature(&new_state)) {
+                        let estimate = moves + 1 + self.distance_to_exit(*node);
+                        nodes.push((new_state, moves + 1, Some(index)));
+                        open.push(Reverse((estimate, nodes.len() - 1)));
+                    }
+                }
+            }
+        }
+        trace
+    }
+
+    // Shortest walk to any field, picking up keys and opening doors on the way when it has to
+    pub fn search_for_path_to(&self, state: State, target: (usize, usize)) -> Option<Vec<(usize, usize)>>{
+        let walls_graph = self.get_walls_graph();
+        let mut nodes: Vec<(State, Option<usize>)> = vec![(state, None)];
+        let mut seen = HashSet::from([signature(&nodes[0].0)]);
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(index) = queue.pop_front(){
+            let position = nodes[index].0.position;
+            if position == target {
+                let mut path = Vec::new();
+                let mut current = Some(index);
+                while let Some(node) = current {
+                    path.push(nodes[node].0.position);
+                    current = nodes[node].1;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if self.exits.contains(&position) && index > 0 {
+                continue;
+            }
+
+            let mut neighbours: Vec<&(usize, usize)> = walls_graph.get(&position).unwrap().iter().collect();
+            neighbours.sort();
+            for node in neighbours{
+                if let Some(new_state) = nodes[index].0.transfer_state(node){
+                    if seen.insert(signature(&new_state)) {
+                        nodes.push((new_state, Some(index)));
+                        queue.push_back(nodes.len() - 1);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,53 @@
+use std::{io::{Error, ErrorKind}, str::FromStr};
+
+use super::{Maze, state::State, search::SearchTrace};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Solver{
+    Sequential,
+    Parallel,
+    // The breadth-first searches keep whole histories instead of a visited set and blow up on large mazes
+    #[default]
+    AStar
+}
+
+impl FromStr for Solver{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sequential" | "seq" => Ok(Solver::Sequential),
+            "parallel" | "par" => Ok(Solver::Parallel),
+            "astar" | "a-star" | "a*" => Ok(Solver::AStar),
+            _ => Err(Error::new(ErrorKind::InvalidInput, format!("Unknown solver '{s}'!")))
+        }
+    }
+}
+
+impl Solver{
+    pub const ALL: [Solver; 3] = [Solver::Sequential, Solver::Parallel, Solver::AStar];
+
+    pub fn name(&self) -> &'static str{
+        match self {
+            Solver::Sequential => "sequential",
+            Solver::Parallel => "parallel",
+            Solver::AStar => "a-star"
+        }
+    }
+
+    pub fn solve(&self, maze: &Maze, state: State) -> Option<Vec<(usize, usize)>>{
+        match self {
+            Solver::Sequential => maze.search_for_shortest_path(state),
+            Solver::Parallel => maze.search_for_shortest_path_parallel(state),
+            Solver::AStar => maze.search_a_star(state).path
+        }
+    }
+
+    // The parallel search expands states in the same order as the sequential one, it only checks the neighbours on separate threads
+    pub fn trace(&self, maze: &Maze, state: State) -> SearchTrace{
+        match self {
+            Solver::Sequential | Solver::Parallel => maze.search_for_shortest_path_traced(state),
+            Solver::AStar => maze.search_a_star(state)
+        }
+    }
+}
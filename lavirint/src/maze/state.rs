@@ -8,7 +8,7 @@ pub enum UnlockDoor{
     NoDoor,
     Unlocked
 }
-#[derive(Clone, Eq, Debug)]
+#[derive(Clone, Default, Eq, Debug)]
 pub struct State{
     pub position: (usize, usize),
     pub keys: usize,
@@ -17,25 +17,13 @@ pub struct State{
     pub shortest_path: Option<Vec<(usize, usize)>>
 }
 
-impl Default for State{
-    fn default() -> Self {
-        Self {
-            position: (0, 0),
-            keys: 0,
-            doors_graph: HashMap::new(),
-            keys_set: HashSet::new(),
-            shortest_path: None
-        }
-    }
-}
-
 impl State{
     pub fn transfer_state(&self, new_position: &(usize, usize)) -> Option<Self>{
         let mut ret = self.clone();
         match ret.unlock_door(new_position) {
             UnlockDoor::NoKey => None,
             _ => {
-                ret.position = new_position.clone();
+                ret.position = *new_position;
                 ret.collect_key(new_position);
                 Some(ret)
             }
@@ -43,7 +31,7 @@ impl State{
     }
 
     pub fn move_to(&mut self, new_position: &(usize, usize)){
-        self.position = new_position.clone();
+        self.position = *new_position;
     }
 
     pub fn create_from_maze(maze: &Maze) -> Self {
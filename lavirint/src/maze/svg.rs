@@ -0,0 +1,137 @@
+use std::{fs, io::Error};
+
+use super::layout::{Layout, Piece, Rect, FIELD_COLOR, WALL_COLOR, DOOR_COLOR, SOLUTION_FIELD_COLOR, KEY_ICON_COLOR, EXIT_ICON_COLOR, START_ICON_COLOR};
+use super::Maze;
+
+const TEXT_COLOR: &str = "000000";
+
+#[derive(Clone)]
+pub struct SvgOptions<'a>{
+    pub cell_size: f32,
+    pub path: Option<&'a [(usize, usize)]>,
+    pub coordinates: bool,
+    pub icons: bool,
+    pub title: Option<&'a str>
+}
+
+impl Default for SvgOptions<'_>{
+    fn default() -> Self {
+        Self{ cell_size: 40., path: None, coordinates: false, icons: true, title: None }
+    }
+}
+
+fn rect(rect: &Rect, color: &str) -> String{
+    format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{color}\"/>\n", rect.x, rect.y, rect.w, rect.h)
+}
+
+fn text(x: f32, y: f32, size: f32, content: &str) -> String{
+    format!(
+        "<text x=\"{x}\" y=\"{y}\" font-size=\"{size}\" font-family=\"sans-serif\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"#{TEXT_COLOR}\">{}</text>\n",
+        escape(content)
+    )
+}
+
+fn escape(content: &str) -> String{
+    content.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn key_icon(area: &Rect) -> String{
+    let (cx, cy) = (area.x + area.w / 2., area.y + area.h / 2.);
+    let radius = area.h * 0.2;
+    let stroke = area.h * 0.08;
+    let shaft = Rect{ x: cx - area.w * 0.1, y: cy - stroke / 2., w: area.w * 0.55, h: stroke };
+    let tooth = |x: f32| Rect{ x, y: cy, w: stroke, h: area.h * 0.2 };
+    format!(
+        "<circle cx=\"{}\" cy=\"{cy}\" r=\"{radius}\" fill=\"none\" stroke=\"#{KEY_ICON_COLOR}\" stroke-width=\"{stroke}\"/>\n{}{}{}",
+        cx - area.w * 0.25,
+        rect(&shaft, KEY_ICON_COLOR),
+        rect(&tooth(shaft.x + shaft.w - stroke), KEY_ICON_COLOR),
+        rect(&tooth(shaft.x + shaft.w - stroke * 2.5), KEY_ICON_COLOR)
+    )
+}
+
+fn exit_icon(area: &Rect) -> String{
+    let stroke = area.h * 0.08;
+    let (x, y, w, h) = (area.x, area.y, area.w, area.h);
+    format!(
+        "<rect x=\"{}\" y=\"{y}\" width=\"{}\" height=\"{h}\" fill=\"none\" stroke=\"#{EXIT_ICON_COLOR}\" stroke-width=\"{stroke}\"/>\n\
+         <polygon points=\"{},{} {},{} {},{}\" fill=\"#{EXIT_ICON_COLOR}\"/>\n",
+        x + w * 0.1, w * 0.5,
+        x + w * 0.5, y + h * 0.25,
+        x + w * 0.95, y + h * 0.5,
+        x + w * 0.5, y + h * 0.75
+    )
+}
+
+impl Maze{
+    pub fn to_svg(&self, options: &SvgOptions) -> String{
+        let layout = Layout::new(options.cell_size, options.cell_size);
+        let (width, height) = layout.size(self);
+        let margin = if options.coordinates { options.cell_size * 0.6 } else { options.cell_size * 0.2 };
+        let title_height = if options.title.is_some() { options.cell_size } else { 0. };
+        let (total_width, total_height) = (width + 2. * margin, height + 2. * margin + title_height);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{total_width}\" height=\"{total_height}\" viewBox=\"0 0 {total_width} {total_height}\">\n"
+        );
+        svg += "<rect width=\"100%\" height=\"100%\" fill=\"#FFFFFF\"/>\n";
+        if let Some(title) = options.title {
+            svg += &text(total_width / 2., title_height / 2. + margin / 2., options.cell_size * 0.45, title);
+        }
+        svg += &format!("<g transform=\"translate({margin} {})\">\n", margin + title_height);
+
+        svg += &rect(&Rect{ x: 0., y: 0., w: width, h: height }, FIELD_COLOR);
+        if let Some(path) = options.path {
+            for position in path.iter(){
+                svg += &rect(&layout.field_rect(*position), SOLUTION_FIELD_COLOR);
+            }
+            let points = path.iter()
+                .map(|position| {
+                    let (x, y) = layout.field_center(*position);
+                    format!("{x},{y}")
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+            svg += &format!(
+                "<polyline points=\"{points}\" fill=\"none\" stroke=\"#{WALL_COLOR}\" stroke-opacity=\"0.5\" stroke-width=\"{}\" stroke-linejoin=\"round\"/>\n",
+                options.cell_size * 0.06
+            );
+        }
+
+        if options.icons {
+            for (y, row) in self.fields.iter().enumerate(){
+                for (x, field) in row.iter().enumerate(){
+                    if field.exit {
+                        svg += &exit_icon(&layout.key_rect((y, x)));
+                    }
+                    if field.key {
+                        svg += &key_icon(&layout.key_rect((y, x)));
+                    }
+                }
+            }
+            let (x, y) = layout.field_center(self.start);
+            svg += &format!("<circle cx=\"{x}\" cy=\"{y}\" r=\"{}\" fill=\"#{START_ICON_COLOR}\"/>\n", options.cell_size * 0.15);
+        }
+
+        for (piece, _, area) in layout.pieces(self){
+            svg += &rect(&area, if piece == Piece::Door { DOOR_COLOR } else { WALL_COLOR });
+        }
+
+        if options.coordinates {
+            let size = options.cell_size * 0.3;
+            for col in 0..self.dimensions.1{
+                svg += &text(layout.field_center((0, col)).0, -margin / 2., size, &col.to_string());
+            }
+            for row in 0..self.dimensions.0{
+                svg += &text(-margin / 2., layout.field_center((row, 0)).1, size, &row.to_string());
+            }
+        }
+
+        svg += "</g>\n</svg>\n";
+        svg
+    }
+
+    pub fn save_svg(&self, file_path: &str, options: &SvgOptions) -> Result<(), Error>{
+        fs::write(file_path, self.to_svg(options))
+    }
+}
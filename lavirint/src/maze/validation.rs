@@ -0,0 +1,124 @@
+use std::{collections::{HashSet, VecDeque}, fmt::Display};
+
+use super::{Maze, state::State};
+
+const DIRECTION_NAMES: [&str; 4] = ["left", "right", "up", "down"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity{
+    Warning,
+    Error
+}
+
+#[derive(Clone, Debug)]
+pub struct Issue{
+    pub severity: Severity,
+    pub position: Option<(usize, usize)>,
+    pub message: String
+}
+
+impl Display for Issue{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error"
+        };
+        match self.position {
+            Some((row, col)) => write!(f, "{severity} at ({row}, {col}): {}", self.message),
+            None => write!(f, "{severity}: {}", self.message)
+        }
+    }
+}
+
+impl Maze{
+    pub fn validate(&self) -> Vec<Issue>{
+        let mut issues = Vec::new();
+        let mut door_count = 0;
+
+        for (row_index, row) in self.fields.iter().enumerate(){
+            for (col_index, field) in row.iter().enumerate(){
+                let position = (row_index, col_index);
+                for (direction, name) in DIRECTION_NAMES.iter().enumerate(){
+                    match self.get_neighbour(&position, direction) {
+                        None => {
+                            if !field.walls[direction] {
+                                issues.push(Issue{
+                                    severity: Severity::Warning,
+                                    position: Some(position),
+                                    message: format!("passage {name} leads out of the maze")
+                                });
+                            }
+                            if field.doors[direction] {
+                                issues.push(Issue{
+                                    severity: Severity::Warning,
+                                    position: Some(position),
+                                    message: format!("door {name} leads out of the maze")
+                                });
+                            }
+                        },
+                        Some(other) => {
+                            let other_field = &self.fields[other.0][other.1];
+                            let back = direction ^ 1;
+                            // Each shared edge is reported once, from the field that comes first
+                            if position < other && field.walls[direction] != other_field.walls[back] {
+                                issues.push(Issue{
+                                    severity: Severity::Warning,
+                                    position: Some(position),
+                                    message: format!("wall {name} doesn't match the neighbour at ({}, {}), the passage is one-way", other.0, other.1)
+                                });
+                            }
+                            if field.doors[direction] && field.walls[direction] {
+                                issues.push(Issue{
+                                    severity: Severity::Warning,
+                                    position: Some(position),
+                                    message: format!("door {name} is placed on a wall and can never be used")
+                                });
+                            }
+                            if field.doors[direction] && !field.walls[direction] {
+                                door_count += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.exits.is_empty() {
+            issues.push(Issue{ severity: Severity::Error, position: None, message: "maze doesn't have an exit".to_string() });
+        }
+
+        let key_count = self.get_keys_set().len();
+        if key_count < door_count {
+            issues.push(Issue{
+                severity: Severity::Warning,
+                position: None,
+                message: format!("{door_count} doors but only {key_count} keys")
+            });
+        }
+
+        let walls_graph = self.get_walls_graph();
+        let mut reachable = HashSet::from([self.start]);
+        let mut queue = VecDeque::from([self.start]);
+        while let Some(current) = queue.pop_front(){
+            for next in walls_graph.get(&current).unwrap(){
+                if reachable.insert(*next) {
+                    queue.push_back(*next);
+                }
+            }
+        }
+        let unreachable = self.dimensions.0 * self.dimensions.1 - reachable.len();
+        if unreachable > 0 {
+            issues.push(Issue{
+                severity: Severity::Warning,
+                position: None,
+                message: format!("{unreachable} fields can't be reached from the start")
+            });
+        }
+
+        if !self.exits.is_empty() && self.search_a_star(State::create_from_maze(self)).path.is_none() {
+            issues.push(Issue{ severity: Severity::Error, position: None, message: "no exit can be reached from the start".to_string() });
+        }
+
+        issues
+    }
+}
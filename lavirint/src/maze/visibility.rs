@@ -0,0 +1,28 @@
+use std::collections::HashSet;
+
+use super::{Maze, state::State};
+
+impl Maze{
+    // Fields in a straight line from the player along open passages, walls and locked doors block the view
+    pub fn visible_from(&self, state: &State, radius: usize) -> HashSet<(usize, usize)>{
+        let mut visible = HashSet::from([state.position]);
+        for direction in 0..4 {
+            let mut current = state.position;
+            for _ in 0..radius {
+                if self.fields[current.0][current.1].walls[direction] {
+                    break;
+                }
+                let next = match self.get_neighbour(&current, direction) {
+                    Some(next) => next,
+                    None => break
+                };
+                if state.doors_graph.get(&current).is_some_and(|doors| doors.contains(&next)) {
+                    break;
+                }
+                visible.insert(next);
+                current = next;
+            }
+        }
+        visible
+    }
+}
@@ -0,0 +1,136 @@
+use std::io::{stdout, Error, Stdout, Write};
+
+use crossterm::{
+    cursor, execute, queue,
+    event::{self, Event, KeyCode, KeyEventKind},
+    style::Print,
+    terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen}
+};
+
+use crate::maze::{Maze, solver::Solver, state::State, render::{Charset, RenderOptions, LEGEND}};
+
+struct Game{
+    maze: Maze,
+    solver: Solver,
+    state: State,
+    moves: usize,
+    solution: Option<Vec<(usize, usize)>>,
+    showing_solution: bool,
+    message: String
+}
+
+impl Game{
+    fn new(maze: Maze, solver: Solver) -> Self{
+        let state = State::create_from_maze(&maze);
+        Self{ maze, solver, state, moves: 0, solution: None, showing_solution: false, message: String::new() }
+    }
+
+    fn restart(&mut self){
+        self.state = State::create_from_maze(&self.maze);
+        self.moves = 0;
+        self.solution = None;
+        self.message = "Restarted.".to_string();
+    }
+
+    fn finished(&self) -> bool{
+        self.maze.exits.contains(&self.state.position)
+    }
+
+    fn step(&mut self, direction: usize){
+        let target = match self.maze.get_neighbour(&self.state.position, direction) {
+            Some(target) if self.maze.get_direct_neighbours(&self.state.position).contains(&target) => target,
+            _ => {
+                self.message = "There's a wall in the way.".to_string();
+                return;
+            }
+        };
+        let keys_before = self.state.keys_set.len();
+        let keys_held = self.state.keys;
+        match self.state.transfer_state(&target) {
+            Some(new_state) => {
+                self.message = if new_state.keys_set.len() < keys_before {
+                    "You picked up a key.".to_string()
+                }else if new_state.keys < keys_held {
+                    "You unlocked a door.".to_string()
+                }else{
+                    String::new()
+                };
+                self.state = new_state;
+                self.moves += 1;
+                self.solution = None;
+            },
+            None => self.message = "The door is locked, find a key first.".to_string()
+        }
+    }
+
+    fn toggle_solution(&mut self){
+        self.showing_solution = !self.showing_solution;
+        if self.showing_solution && self.solution.is_none() {
+            self.solution = self.solver.solve(&self.maze, self.state.clone());
+            if self.solution.is_none() {
+                self.message = "No solution from here...".to_string();
+            }
+        }
+    }
+
+    fn render(&self, out: &mut Stdout) -> Result<(), Error>{
+        let options = RenderOptions{
+            charset: Charset::Unicode,
+            state: Some(&self.state),
+            player: Some(self.state.position),
+            path: if self.showing_solution { self.solution.as_deref() } else { None }
+        };
+        queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+        for line in self.maze.render_lines(&options){
+            queue!(out, Print(line), Print("\r\n"))?;
+        }
+        queue!(
+            out,
+            Print(format!("\r\nKeys: {}   Moves: {}\r\n", self.state.keys, self.moves)),
+            Print("Arrows/WASD - move   Shift+S - solution   R - restart   Q - quit\r\n"),
+            Print(format!("{LEGEND}\r\n"))
+        )?;
+        if self.finished() {
+            queue!(out, Print(format!("\r\nYou escaped in {} moves! Press any key to quit.\r\n", self.moves)))?;
+        }else if !self.message.is_empty() {
+            queue!(out, Print(format!("\r\n{}\r\n", self.message)))?;
+        }
+        out.flush()
+    }
+}
+
+fn run(game: &mut Game, out: &mut Stdout) -> Result<(), Error>{
+    loop {
+        game.render(out)?;
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue
+        };
+        if game.finished() {
+            return Ok(());
+        }
+        match key.code {
+            KeyCode::Left | KeyCode::Char('a') => game.step(0),
+            KeyCode::Right | KeyCode::Char('d') => game.step(1),
+            KeyCode::Up | KeyCode::Char('w') => game.step(2),
+            KeyCode::Down | KeyCode::Char('s') => game.step(3),
+            KeyCode::Char('S') => game.toggle_solution(),
+            KeyCode::Char('r') => game.restart(),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+pub fn play(maze: Maze, solver: Solver) -> Result<(), Error>{
+    let mut game = Game::new(maze, solver);
+    let mut out = stdout();
+
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, cursor::Hide)?;
+    let result = run(&mut game, &mut out);
+    execute!(out, cursor::Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
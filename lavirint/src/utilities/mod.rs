@@ -1,13 +1,14 @@
-use std::fs::File;
+use std::fs::{File, read_dir};
 use std::io::{Read, Write, Error};
+use std::path::{Path, PathBuf};
 
-pub fn convert_string_to_u8(content: &String) -> Vec<u8>{
+pub fn convert_string_to_u8(content: &str) -> Vec<u8>{
     let mut data = Vec::<u8>::new();
     let mut counter = 7;
     let mut value = 0;
     for c in content.split(""){
         if c == "1" {
-            value += (2 as u8).pow(counter);
+            value += 2u8.pow(counter);
         }
         if c == "1" || c == "0" {
             if counter == 0 {
@@ -21,17 +22,17 @@ pub fn convert_string_to_u8(content: &String) -> Vec<u8>{
     if counter < 7 {
         data.push(value);
     }
-    return data;
+    data
 }
 
 pub fn convert_txt_to_bin(txt_file_path: &String, bin_file_path:&String){
-    let mut file = File::open(txt_file_path).expect(format!("Couldn't create the file '{txt_file_path}'!").as_str());
+    let mut file = File::open(txt_file_path).unwrap_or_else(|_| panic!("Couldn't create the file '{txt_file_path}'!"));
     let mut content = String::new();
-    file.read_to_string(&mut content).expect(format!("Couldn't read from the file '{txt_file_path}'!").as_str());
+    file.read_to_string(&mut content).unwrap_or_else(|_| panic!("Couldn't read from the file '{txt_file_path}'!"));
     let data = convert_string_to_u8(&content);
 
-    let mut bin_file = File::create(bin_file_path).expect(format!("Couldn't create the file '{bin_file_path}'!").as_str());
-    bin_file.write_all(&data).expect(format!("Couldn't write to '{bin_file_path}'!").as_str());
+    let mut bin_file = File::create(bin_file_path).unwrap_or_else(|_| panic!("Couldn't create the file '{bin_file_path}'!"));
+    bin_file.write_all(&data).unwrap_or_else(|_| panic!("Couldn't write to '{bin_file_path}'!"));
 }
 
 pub fn read_binary(bin_file_path :&String) -> Result<Vec<u8>, Error>{
@@ -41,4 +42,34 @@ pub fn read_binary(bin_file_path :&String) -> Result<Vec<u8>, Error>{
     file.read_to_end(&mut data)?;
 
     Ok(data)
+}
+
+pub fn read_text(txt_file_path: &String) -> Result<String, Error>{
+    let mut content = String::new();
+
+    let mut file = File::open(txt_file_path)?;
+    file.read_to_string(&mut content)?;
+
+    Ok(content)
+}
+
+// An optional '.fog' file next to a maze holds how many fields far the player sees in fog of war mode
+pub fn read_visibility_radius(maze_path: &Path) -> Option<usize>{
+    let fog_path = maze_path.with_extension("fog").to_string_lossy().to_string();
+    read_text(&fog_path).ok()?.trim().parse().ok()
+}
+
+pub fn list_maze_files(dir_path: &String) -> Result<Vec<PathBuf>, Error>{
+    let mut files = Vec::new();
+
+    for entry in read_dir(dir_path)?{
+        let path = entry?.path();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("txt") | Some("bin") | Some("lab") => files.push(path),
+            _ => {}
+        }
+    }
+    files.sort();
+
+    Ok(files)
 }
\ No newline at end of file
@@ -0,0 +1,146 @@
+use bevy::{prelude::*, ecs::system::SystemParam, input::mouse::{MouseWheel, MouseScrollUnit}};
+
+use super::{AppState, WinSize, input::{Actions, Action}, maze_visual::MazeVisualState, player::Player};
+
+const FOLLOW_RATE: f32 = 6.;
+const ZOOM_STEP: f32 = 0.1;
+const MIN_ZOOM: f32 = 0.25;
+const PIXELS_PER_LINE: f32 = 100.;
+
+pub struct CameraPlugin;
+
+#[derive(Resource)]
+pub struct CameraState {
+    pub follow: bool,
+    pub fitted: bool,
+    pub drag: Option<Vec2>
+}
+
+impl Default for CameraState {
+    fn default() -> Self {
+        Self { follow: true, fitted: false, drag: None }
+    }
+}
+
+impl Plugin for CameraPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(CameraState::default())
+        .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(camera_reset_system))
+        .add_system_set(SystemSet::on_exit(AppState::Playing).with_system(camera_reset_system))
+        // The replay panels are laid out for the plain window
+        .add_system_set(SystemSet::on_enter(AppState::Replay).with_system(camera_reset_system))
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(camera_input_system.label("camera-input"))
+            .with_system(camera_follow_system.after("camera-input").after("movement"))
+        );
+    }
+}
+
+fn camera_reset_system(
+    mut camera_state: ResMut<CameraState>,
+    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>
+){
+    *camera_state = CameraState::default();
+    for (mut transform, mut projection) in query.iter_mut(){
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+        projection.scale = 1.;
+    }
+}
+
+// Zoom that shows the whole maze with its frame
+fn fit_scale(win_size: &WinSize, maze_visual_state: &MazeVisualState) -> f32{
+    let needed = maze_visual_state.size + Vec2::splat(2. * win_size.frame_size);
+    (needed.x / win_size.w).max(needed.y / win_size.h).max(1.)
+}
+
+// Zooming and panning come straight from the mouse, they have no actions
+#[derive(SystemParam)]
+pub struct CameraMouse<'w, 's>{
+    buttons: Res<'w, Input<MouseButton>>,
+    windows: Res<'w, Windows>,
+    wheel: EventReader<'w, 's, MouseWheel>
+}
+
+fn camera_input_system(
+    actions: Res<Actions>,
+    mut mouse: CameraMouse,
+    win_size: Res<WinSize>,
+    maze_visual_state: Res<MazeVisualState>,
+    mut camera_state: ResMut<CameraState>,
+    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>
+){
+    let (mut transform, mut projection) = match query.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return
+    };
+    let max_zoom = fit_scale(&win_size, &maze_visual_state);
+
+    if actions.just_pressed(Action::Fit){
+        camera_state.fitted = !camera_state.fitted;
+        camera_state.follow = !camera_state.fitted;
+        projection.scale = if camera_state.fitted { max_zoom } else { 1. };
+        if camera_state.fitted {
+            transform.translation.x = 0.;
+            transform.translation.y = 0.;
+        }
+    }
+    if [Action::MoveLeft, Action::MoveRight, Action::MoveUp, Action::MoveDown].into_iter().any(|action| actions.just_pressed(action)){
+        camera_state.follow = true;
+    }
+
+    for event in mouse.wheel.iter(){
+        let lines = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE
+        };
+        projection.scale = (projection.scale * (1. - ZOOM_STEP * lines)).clamp(MIN_ZOOM, max_zoom);
+        camera_state.fitted = false;
+    }
+
+    // Left clicks walk the player, so panning uses the other buttons
+    let cursor = mouse.windows.get_primary().and_then(|window| window.cursor_position());
+    if mouse.buttons.pressed(MouseButton::Right) || mouse.buttons.pressed(MouseButton::Middle){
+        if let (Some(last), Some(cursor)) = (camera_state.drag, cursor) {
+            let delta = (cursor - last) * projection.scale;
+            transform.translation.x -= delta.x;
+            transform.translation.y -= delta.y;
+            if delta != Vec2::ZERO {
+                camera_state.follow = false;
+            }
+        }
+        camera_state.drag = cursor;
+    }else{
+        camera_state.drag = None;
+    }
+}
+
+fn camera_follow_system(
+    time: Res<Time>,
+    win_size: Res<WinSize>,
+    maze_visual_state: Res<MazeVisualState>,
+    camera_state: Res<CameraState>,
+    player_query: Query<&Transform, (With<Player>, Without<Camera2d>)>,
+    mut query: Query<(&mut Transform, &OrthographicProjection), With<Camera2d>>
+){
+    let (mut transform, projection) = match query.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return
+    };
+    let mut target = transform.translation.truncate();
+    if camera_state.follow {
+        if let Ok(player) = player_query.get_single() {
+            let t = 1. - (-FOLLOW_RATE * time.delta_seconds()).exp();
+            target = target.lerp(player.translation.truncate(), t);
+        }
+    }
+
+    // Keep the view over the maze, a maze smaller than the view stays centred
+    let half_view = Vec2::new(win_size.w, win_size.h) * projection.scale / 2.;
+    let half_maze = maze_visual_state.size / 2. + Vec2::splat(win_size.frame_size);
+    let room = (half_maze - half_view).max(Vec2::ZERO);
+    target = target.clamp(-room, room);
+    transform.translation.x = target.x;
+    transform.translation.y = target.y;
+}
@@ -0,0 +1,315 @@
+use std::{collections::HashMap, fs, io::{Error, ErrorKind}, time::SystemTime};
+
+use bevy::{prelude::*, ecs::system::SystemParam};
+use serde::Deserialize;
+
+use crate::maze::layout::{hex_to_rgb, FIELD_COLOR, WALL_COLOR, DOOR_COLOR, SOLUTION_FIELD_COLOR, KEY_ICON_COLOR, EXIT_ICON_COLOR, PLAYER_COLOR, WALL_SCALE, DOOR_SCALE, KEY_SCALE};
+
+use super::{GameTextures, input::{Action, Bindings}, maze_visual::Redraw, player::{Player, PlayerState}, PLAYER_ASSET_DIMENSIONS};
+
+pub const CONFIG_PATH: &str = "config.ron";
+const RELOAD_SECONDS: f32 = 1.;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum Preset{
+    #[default]
+    Light,
+    Dark,
+    // Okabe-Ito colours, which stay apart for every kind of colour blindness, with thicker walls
+    HighContrast
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct Theme{
+    pub field: [u8; 3],
+    pub wall: [u8; 3],
+    pub door: [u8; 3],
+    pub solution: [u8; 3],
+    pub key: [u8; 3],
+    pub exit: [u8; 3],
+    pub player: [u8; 3],
+    pub fog: [u8; 3],
+    pub text: [u8; 3],
+    pub background: [u8; 3],
+    pub wall_scale: f32,
+    pub door_scale: f32,
+    pub key_scale: f32,
+    pub player_scale: f32,
+    pub player_sprite: String,
+    pub key_sprite: String,
+    pub exit_sprite: String,
+    pub base_speed: f32,
+    pub sounds: Sounds
+}
+
+// No sounds ship with the game, each one plays only once a file is set for it
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Sounds{
+    pub key: Option<String>,
+    pub door: Option<String>,
+    pub blocked: Option<String>,
+    pub exit: Option<String>
+}
+
+#[derive(Resource)]
+pub struct ConfigWatch{
+    pub timer: Timer,
+    pub modified: Option<SystemTime>
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ColorsFile{
+    field: Option<String>,
+    wall: Option<String>,
+    door: Option<String>,
+    solution: Option<String>,
+    key: Option<String>,
+    exit: Option<String>,
+    player: Option<String>,
+    fog: Option<String>,
+    text: Option<String>,
+    background: Option<String>
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ScalesFile{
+    wall: Option<f32>,
+    door: Option<f32>,
+    key: Option<f32>,
+    player: Option<f32>
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct SpritesFile{
+    player: Option<String>,
+    key: Option<String>,
+    exit: Option<String>
+}
+
+// Everything is optional, whatever the file leaves out comes from the preset
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ConfigFile{
+    preset: Preset,
+    colors: ColorsFile,
+    scales: ScalesFile,
+    sprites: SpritesFile,
+    sounds: Sounds,
+    base_speed: Option<f32>,
+    bindings: HashMap<Action, Vec<KeyCode>>,
+    gamepad: HashMap<Action, Vec<GamepadButtonType>>
+}
+
+impl Theme{
+    pub fn preset(preset: Preset) -> Self{
+        let light = Self{
+            field: hex_to_rgb(FIELD_COLOR),
+            wall: hex_to_rgb(WALL_COLOR),
+            door: hex_to_rgb(DOOR_COLOR),
+            solution: hex_to_rgb(SOLUTION_FIELD_COLOR),
+            key: hex_to_rgb(KEY_ICON_COLOR),
+            exit: hex_to_rgb(EXIT_ICON_COLOR),
+            player: hex_to_rgb(PLAYER_COLOR),
+            fog: hex_to_rgb("2B2340"),
+            text: hex_to_rgb("000000"),
+            background: hex_to_rgb("FFFFFF"),
+            wall_scale: WALL_SCALE,
+            door_scale: DOOR_SCALE,
+            key_scale: KEY_SCALE,
+            player_scale: 0.65,
+            player_sprite: "player.png".to_string(),
+            key_sprite: "key.png".to_string(),
+            exit_sprite: "exit.png".to_string(),
+            base_speed: 500.,
+            sounds: Sounds::default()
+        };
+        match preset {
+            Preset::Light => light,
+            Preset::Dark => Self{
+                field: hex_to_rgb("2A2438"),
+                wall: hex_to_rgb("D8CFF0"),
+                door: hex_to_rgb("3FB6E0"),
+                solution: hex_to_rgb("35607A"),
+                key: hex_to_rgb("E0B84A"),
+                exit: hex_to_rgb("4CB07A"),
+                player: hex_to_rgb("F5A04A"),
+                fog: hex_to_rgb("0D0A14"),
+                text: hex_to_rgb("F0F0F0"),
+                background: hex_to_rgb("14111C"),
+                ..light
+            },
+            Preset::HighContrast => Self{
+                field: hex_to_rgb("FFFFFF"),
+                wall: hex_to_rgb("000000"),
+                door: hex_to_rgb("0072B2"),
+                solution: hex_to_rgb("56B4E9"),
+                key: hex_to_rgb("E69F00"),
+                exit: hex_to_rgb("009E73"),
+                player: hex_to_rgb("D55E00"),
+                fog: hex_to_rgb("000000"),
+                wall_scale: 0.06,
+                door_scale: 0.1,
+                ..light
+            }
+        }
+    }
+
+    pub fn color(rgb: [u8; 3]) -> Color{
+        Color::rgb_u8(rgb[0], rgb[1], rgb[2])
+    }
+
+    pub fn translucent(rgb: [u8; 3], alpha: u8) -> Color{
+        Color::rgba_u8(rgb[0], rgb[1], rgb[2], alpha)
+    }
+}
+
+impl Default for Theme{
+    fn default() -> Self {
+        Self::preset(Preset::default())
+    }
+}
+
+fn parse_color(name: &str, hex: &Option<String>, default: [u8; 3]) -> Result<[u8; 3], Error>{
+    match hex {
+        None => Ok(default),
+        Some(hex) => {
+            let hex = hex.trim_start_matches('#');
+            if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                Ok(hex_to_rgb(hex))
+            }else{
+                Err(Error::new(ErrorKind::InvalidData, format!("Colour '{name}' should be six hex digits, not '{hex}'!")))
+            }
+        }
+    }
+}
+
+pub fn load_config(path: &str) -> Result<(Theme, Bindings), Error>{
+    let content = fs::read_to_string(path)?;
+    // Optional values can be written without Some(...)
+    let file: ConfigFile = ron::Options::default()
+        .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+        .from_str(&content)
+        .map_err(|er| Error::new(ErrorKind::InvalidData, format!("Couldn't parse '{path}': {er}")))?;
+
+    let preset = Theme::preset(file.preset);
+    let colors = &file.colors;
+    let theme = Theme{
+        field: parse_color("field", &colors.field, preset.field)?,
+        wall: parse_color("wall", &colors.wall, preset.wall)?,
+        door: parse_color("door", &colors.door, preset.door)?,
+        solution: parse_color("solution", &colors.solution, preset.solution)?,
+        key: parse_color("key", &colors.key, preset.key)?,
+        exit: parse_color("exit", &colors.exit, preset.exit)?,
+        player: parse_color("player", &colors.player, preset.player)?,
+        fog: parse_color("fog", &colors.fog, preset.fog)?,
+        text: parse_color("text", &colors.text, preset.text)?,
+        background: parse_color("background", &colors.background, preset.background)?,
+        wall_scale: file.scales.wall.unwrap_or(preset.wall_scale),
+        door_scale: file.scales.door.unwrap_or(preset.door_scale),
+        key_scale: file.scales.key.unwrap_or(preset.key_scale),
+        player_scale: file.scales.player.unwrap_or(preset.player_scale),
+        player_sprite: file.sprites.player.unwrap_or(preset.player_sprite),
+        key_sprite: file.sprites.key.unwrap_or(preset.key_sprite),
+        exit_sprite: file.sprites.exit.unwrap_or(preset.exit_sprite),
+        base_speed: file.base_speed.unwrap_or(preset.base_speed),
+        sounds: file.sounds
+    };
+
+    // Actions the file doesn't mention keep their default keys and buttons
+    let mut bindings = Bindings::default();
+    bindings.keys.extend(file.bindings);
+    bindings.buttons.extend(file.gamepad);
+    Ok((theme, bindings))
+}
+
+// A missing file just means the defaults, a broken one is reported and the defaults are used
+pub fn load_config_or_default(path: &str) -> (Theme, Bindings){
+    match load_config(path) {
+        Ok(config) => config,
+        Err(er) if er.kind() == ErrorKind::NotFound => (Theme::default(), Bindings::default()),
+        Err(er) => {
+            eprintln!("{er}");
+            (Theme::default(), Bindings::default())
+        }
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime>{
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin{
+    fn build(&self, app: &mut App){
+        let (theme, bindings) = load_config_or_default(CONFIG_PATH);
+        app.insert_resource(ClearColor(Theme::color(theme.background)))
+        .insert_resource(theme)
+        .insert_resource(bindings)
+        .insert_resource(ConfigWatch{
+            timer: Timer::from_seconds(RELOAD_SECONDS, TimerMode::Repeating),
+            modified: modified(CONFIG_PATH)
+        })
+        .add_system(config_reload_system);
+    }
+}
+
+// Everything outside the maze that changes with the theme
+#[derive(SystemParam)]
+pub struct Look<'w, 's>{
+    asset_server: Res<'w, AssetServer>,
+    clear_color: ResMut<'w, ClearColor>,
+    game_textures: ResMut<'w, GameTextures>,
+    player_state: ResMut<'w, PlayerState>,
+    query: Query<'w, 's, (&'static mut Handle<Image>, &'static mut Sprite), With<Player>>
+}
+
+impl<'w, 's> Look<'w, 's>{
+    fn apply(&mut self, theme: &Theme){
+        self.clear_color.0 = Theme::color(theme.background);
+        self.game_textures.player = self.asset_server.load(theme.player_sprite.as_str());
+        self.game_textures.key = self.asset_server.load(theme.key_sprite.as_str());
+        self.game_textures.exit = self.asset_server.load(theme.exit_sprite.as_str());
+        self.player_state.size = Vec2::new(PLAYER_ASSET_DIMENSIONS.0, PLAYER_ASSET_DIMENSIONS.1) * theme.player_scale;
+        for (mut texture, mut sprite) in self.query.iter_mut(){
+            *texture = self.game_textures.player.clone();
+            sprite.custom_size = Some(self.player_state.size);
+        }
+    }
+}
+
+// Polls the file instead of watching it, so hot reload works without any extra asset server features
+fn config_reload_system(
+    time: Res<Time>,
+    mut watch: ResMut<ConfigWatch>,
+    mut theme: ResMut<Theme>,
+    mut bindings: ResMut<Bindings>,
+    mut look: Look,
+    mut redraw: EventWriter<Redraw>
+){
+    if !watch.timer.tick(time.delta()).just_finished(){
+        return;
+    }
+    let current = modified(CONFIG_PATH);
+    if current == watch.modified {
+        return;
+    }
+    watch.modified = current;
+    let (new_theme, new_bindings) = match load_config(CONFIG_PATH) {
+        Ok(config) => config,
+        Err(er) => {
+            eprintln!("Keeping the current settings: {er}");
+            return;
+        }
+    };
+
+    look.apply(&new_theme);
+    *theme = new_theme;
+    *bindings = new_bindings;
+    // The maze is drawn again with the new look
+    redraw.send(Redraw);
+}
@@ -0,0 +1,221 @@
+use bevy::{prelude::*, ecs::system::SystemParam};
+
+use super::{AppState, GameTextures, config::Theme, input::{Actions, Action}, fog::Fog, maze_visual::{MazeVisualState, MazeVisual}, player::{Player, Velocity}};
+
+const ARRIVAL_DISTANCE: f32 = 0.5;
+const NOTICE_SECONDS: f32 = 2.;
+
+pub struct DemoPlugin;
+
+#[derive(Resource, Default)]
+pub struct Demo {
+    pub active: bool,
+    pub path: Vec<(usize, usize)>,
+    pub next: usize,
+    pub interrupted: bool,
+    // Set when walking to a clicked field instead of the exit
+    pub target: Option<(usize, usize)>
+}
+
+#[derive(Component)]
+pub struct Notice{
+    pub timer: Timer
+}
+
+impl Plugin for DemoPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(Demo::default())
+        .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(demo_reset_system))
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(demo_keyboard_event_system.label("demo-input"))
+            .with_system(demo_click_system.label("demo-input"))
+            .with_system(notice_expiry_system)
+            .with_system(demo_walk_system.after("demo-input").after("player-input").before("movement"))
+        );
+    }
+}
+
+fn demo_reset_system(mut demo: ResMut<Demo>){
+    *demo = Demo::default();
+}
+
+// The path starts at the player's field, so the sprite is first centred on it
+fn plan(demo: &mut Demo, maze_visual_state: &mut MazeVisualState) -> bool{
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    let path = match demo.target {
+        Some(target) => maze_visual_state.maze.search_for_path_to(state, target),
+        None => maze_visual_state.solver.solve(&maze_visual_state.maze, state)
+    };
+    match path {
+        Some(path) => {
+            demo.path = path;
+            demo.next = 0;
+            demo.interrupted = false;
+            true
+        },
+        None => false
+    }
+}
+
+fn demo_keyboard_event_system(
+    actions: Res<Actions>,
+    mut demo: ResMut<Demo>,
+    mut maze_visual_state: ResMut<MazeVisualState>
+){
+    if actions.just_pressed(Action::Demo){
+        demo.target = None;
+        demo.active = !demo.active && plan(&mut demo, &mut maze_visual_state);
+    }
+}
+
+// The field under the cursor when the left button goes down
+#[derive(SystemParam)]
+pub struct FieldClick<'w, 's>{
+    mouse: Res<'w, Input<MouseButton>>,
+    windows: Res<'w, Windows>,
+    camera_query: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>
+}
+
+impl FieldClick<'_, '_>{
+    fn field(&self, maze_visual_state: &MazeVisualState) -> Option<(usize, usize)>{
+        if !self.mouse.just_pressed(MouseButton::Left){
+            return None;
+        }
+        let cursor = self.windows.get_primary().and_then(|window| window.cursor_position())?;
+        let (camera, camera_transform) = self.camera_query.get_single().ok()?;
+        camera.viewport_to_world(camera_transform, cursor)
+            .and_then(|ray| maze_visual_state.field_at(ray.origin.truncate()))
+    }
+}
+
+// Only one notice is up at a time, a new one replaces the last
+#[derive(SystemParam)]
+pub struct Notices<'w, 's>{
+    game_textures: Res<'w, GameTextures>,
+    theme: Res<'w, Theme>,
+    query: Query<'w, 's, Entity, With<Notice>>
+}
+
+impl Notices<'_, '_>{
+    fn clear(&self, commands: &mut Commands){
+        for e in self.query.iter(){
+            commands.entity(e).despawn();
+        }
+    }
+
+    fn show(&self, commands: &mut Commands, message: String){
+        commands.spawn(TextBundle{
+            text: Text::from_section(
+                message,
+                TextStyle {
+                    font_size: 30.,
+                    color: Theme::color(self.theme.text),
+                    font: self.game_textures.font.clone()
+                }
+            ),
+            style: Style{
+                position_type: PositionType::Absolute,
+                position: UiRect{ bottom: Val::Px(50.), left: Val::Px(10.), ..default() },
+                ..default()
+            },
+            z_index: ZIndex::Global(30),
+            ..default()
+        })
+        .insert(Notice{ timer: Timer::from_seconds(NOTICE_SECONDS, TimerMode::Once) })
+        .insert(MazeVisual);
+    }
+}
+
+fn demo_click_system(
+    mut commands: Commands,
+    click: FieldClick,
+    notices: Notices,
+    mut demo: ResMut<Demo>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    fog: Res<Fog>
+){
+    let field = match click.field(&maze_visual_state) {
+        Some(field) => field,
+        None => return
+    };
+
+    notices.clear(&mut commands);
+    // Walking into the fog would give away the way through it
+    let message = if fog.is_seen(field) {
+        demo.target = Some(field);
+        demo.active = plan(&mut demo, &mut maze_visual_state);
+        if demo.active{
+            return;
+        }
+        demo.target = None;
+        format!("   Field ({}, {}) can't be reached from here", field.0, field.1)
+    }else{
+        format!("   Field ({}, {}) hasn't been seen yet", field.0, field.1)
+    };
+    notices.show(&mut commands, message);
+}
+
+fn notice_expiry_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Notice)>
+){
+    for (e, mut notice) in query.iter_mut(){
+        if notice.timer.tick(time.delta()).finished(){
+            commands.entity(e).despawn();
+        }
+    }
+}
+
+fn demo_walk_system(
+    actions: Res<Actions>,
+    theme: Res<Theme>,
+    time: Res<Time>,
+    mut demo: ResMut<Demo>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    mut query: Query<(&Transform, &mut Velocity), With<Player>>
+){
+    if !demo.active{
+        return;
+    }
+    let manual = [Action::MoveLeft, Action::MoveRight, Action::MoveUp, Action::MoveDown].into_iter().any(|action| actions.pressed(action));
+    if manual{
+        demo.interrupted = true;
+        return;
+    }
+    if demo.interrupted && !plan(&mut demo, &mut maze_visual_state){
+        demo.active = false;
+        return;
+    }
+
+    let (transform, mut velocity) = match query.get_single_mut() {
+        Ok(player) => player,
+        Err(_) => return
+    };
+    let position = transform.translation.truncate();
+    let mut target = maze_visual_state.field_center(demo.path[demo.next]);
+
+    if position.distance(target) < ARRIVAL_DISTANCE{
+        // Keys, doors and the logical position are all handled by the movement system while walking, like in manual play
+        let logical = maze_visual_state.maze.get_state_mut().position;
+        if logical != demo.path[demo.next] && !plan(&mut demo, &mut maze_visual_state){
+            demo.active = false;
+            return;
+        }
+        if demo.next + 1 >= demo.path.len(){
+            demo.active = false;
+            return;
+        }
+        demo.next += 1;
+        target = maze_visual_state.field_center(demo.path[demo.next]);
+    }
+
+    let step = theme.base_speed * time.delta_seconds();
+    if step <= 0.{
+        return;
+    }
+    let delta = target - position;
+    velocity.x = (delta.x / step).clamp(-1., 1.);
+    velocity.y = (delta.y / step).clamp(-1., 1.);
+}
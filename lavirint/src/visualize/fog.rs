@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+
+use bevy::{prelude::*, sprite::Anchor, render::{render_resource::{Extent3d, TextureDimension, TextureFormat}, texture::ImageSampler}};
+
+use super::{AppState, config::Theme, maze_visual::{MazeVisualState, MazeVisual, Relayout}};
+
+const SEEN_ALPHA: u8 = 140;
+const DEFAULT_VISIBILITY: usize = 4;
+
+pub struct FogPlugin{
+    pub enabled: bool,
+    pub level_radius: Option<usize>
+}
+
+#[derive(Component)]
+pub struct FogLayer;
+
+#[derive(Resource, Default)]
+pub struct Fog {
+    pub enabled: bool,
+    // Set by the level being played, DEFAULT_VISIBILITY otherwise
+    pub level_radius: Option<usize>,
+    pub seen: HashSet<(usize, usize)>,
+    pub visible: HashSet<(usize, usize)>,
+    pub image: Option<Handle<Image>>,
+    pub painted: Option<((usize, usize), usize)>
+}
+
+impl Fog {
+    pub fn radius(&self) -> usize{
+        self.level_radius.unwrap_or(DEFAULT_VISIBILITY)
+    }
+
+    // Everything is known when there's no fog
+    pub fn is_seen(&self, position: (usize, usize)) -> bool{
+        !self.enabled || self.seen.contains(&position)
+    }
+}
+
+impl Plugin for FogPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(Fog{ enabled: self.enabled, level_radius: self.level_radius, ..default() })
+        .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(fog_spawn_system.after("labyrinth-spawn")))
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(fog_sync_system.label("fog-sync").after("movement"))
+            .with_system(fog_relayout_system.after("relayout"))
+        );
+    }
+}
+
+// One texel per field stretched over the whole maze, so the fog is a single sprite however big the maze is
+fn paint(fog: &Fog, dimensions: (usize, usize), color: [u8; 3]) -> Image{
+    let [r, g, b] = color;
+    let mut pixels = Vec::with_capacity(dimensions.0 * dimensions.1 * 4);
+    for y in 0..dimensions.0{
+        for x in 0..dimensions.1{
+            let alpha = if fog.visible.contains(&(y, x)) {
+                0
+            }else if fog.seen.contains(&(y, x)) {
+                SEEN_ALPHA
+            }else{
+                255
+            };
+            pixels.extend([r, g, b, alpha]);
+        }
+    }
+    let mut image = Image::new(
+        Extent3d{ width: dimensions.1 as u32, height: dimensions.0 as u32, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb
+    );
+    image.sampler_descriptor = ImageSampler::nearest();
+    image
+}
+
+fn fog_spawn_system(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut fog: ResMut<Fog>,
+    theme: Res<Theme>,
+    mut maze_visual_state: ResMut<MazeVisualState>
+){
+    fog.seen.clear();
+    fog.visible.clear();
+    fog.painted = None;
+    fog.image = None;
+    if !fog.enabled {
+        return;
+    }
+
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    fog.visible = maze_visual_state.maze.visible_from(&state, fog.radius());
+    let visible = fog.visible.clone();
+    fog.seen.extend(visible);
+    let handle = images.add(paint(&fog, maze_visual_state.maze.dimensions, theme.fog));
+    fog.image = Some(handle.clone());
+
+    commands.spawn(SpriteBundle{
+        texture: handle,
+        sprite: Sprite{
+            custom_size: Some(maze_visual_state.size),
+            anchor: Anchor::Center,
+            ..default()
+        },
+        // Over the walls, doors and keys but under the player
+        transform: Transform::from_translation(Vec3::new(0., 0., 8.)),
+        ..default()
+    })
+    .insert(FogLayer)
+    .insert(MazeVisual);
+}
+
+fn fog_sync_system(
+    mut images: ResMut<Assets<Image>>,
+    mut fog: ResMut<Fog>,
+    theme: Res<Theme>,
+    mut maze_visual_state: ResMut<MazeVisualState>
+){
+    let handle = match (fog.enabled, fog.image.clone()) {
+        (true, Some(handle)) => handle,
+        _ => return
+    };
+    // Opening a door can uncover a corridor without the player moving
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    let locked_doors = state.doors_graph.values().map(|doors| doors.len()).sum();
+    if fog.painted == Some((state.position, locked_doors)) && !theme.is_changed() {
+        return;
+    }
+    fog.visible = maze_visual_state.maze.visible_from(&state, fog.radius());
+    let visible = fog.visible.clone();
+    fog.seen.extend(visible);
+    if let Some(image) = images.get_mut(&handle) {
+        *image = paint(&fog, maze_visual_state.maze.dimensions, theme.fog);
+        fog.painted = Some((state.position, locked_doors));
+    }
+}
+
+fn fog_relayout_system(
+    mut relayout: EventReader<Relayout>,
+    maze_visual_state: Res<MazeVisualState>,
+    mut query: Query<&mut Sprite, With<FogLayer>>
+){
+    if relayout.iter().count() == 0 {
+        return;
+    }
+    for mut sprite in query.iter_mut(){
+        sprite.custom_size = Some(maze_visual_state.size);
+    }
+}
@@ -0,0 +1,234 @@
+use bevy::prelude::*;
+
+use crate::maze::state::State as MazeState;
+
+use super::{AppState, GameTextures, config::Theme, demo::Demo, input::{Actions, Action, Bindings}, maze_visual::{MazeVisualState, Redraw}, player::{PlayerState, CellEntered, DoorBlocked, DoorUnlocked, ExitReached}};
+
+pub struct GamePlugin;
+
+#[derive(Component)]
+pub struct VictoryScreen;
+
+#[derive(Resource)]
+pub struct GameStats {
+    pub elapsed: f64,
+    pub doors_unlocked: usize,
+    pub doors_blocked: usize
+}
+
+// Every state the level has been in, the last one is the current state
+#[derive(Resource, Default)]
+pub struct MoveHistory {
+    pub entries: Vec<HistoryEntry>,
+    // Fields the player entered in order, undo cuts the way back off again
+    pub path: Vec<(usize, usize)>
+}
+
+pub struct HistoryEntry {
+    pub state: MazeState,
+    // How much of the path was walked and how many doors were unlocked by the time the state was reached
+    pub walked: usize,
+    pub doors_unlocked: usize
+}
+
+impl Default for GameStats {
+    fn default() -> Self {
+        Self { elapsed: 0., doors_unlocked: 0, doors_blocked: 0 }
+    }
+}
+
+impl Plugin for GamePlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(GameStats::default())
+        .insert_resource(MoveHistory::default())
+        .add_system_set(
+            SystemSet::on_enter(AppState::Playing)
+            .with_system(game_stats_reset_system)
+            .with_system(start_on_exit_system.after("labyrinth-spawn"))
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(timer_system)
+            .with_system(restart_system)
+            .with_system(undo_system.before("relayout"))
+            .with_system(history_system.after("stats"))
+            .with_system(stats_system.label("stats").after("movement"))
+            .with_system(exit_detection_system.after("stats"))
+        )
+        .add_system_set(SystemSet::on_enter(AppState::Victory).with_system(victory_screen_spawn_system))
+        .add_system_set(SystemSet::on_update(AppState::Victory).with_system(victory_keyboard_event_system))
+        .add_system_set(SystemSet::on_exit(AppState::Victory).with_system(victory_screen_despawn_system));
+    }
+}
+
+fn game_stats_reset_system(mut game_stats: ResMut<GameStats>, mut history: ResMut<MoveHistory>){
+    *game_stats = GameStats::default();
+    *history = MoveHistory::default();
+}
+
+// Leaving and entering the level again respawns it from the start
+fn restart_system(
+    mut actions: ResMut<Actions>,
+    mut app_state: ResMut<State<AppState>>
+){
+    if actions.clear_just_pressed(Action::Restart){
+        app_state.restart().unwrap_or_default();
+    }
+}
+
+// Free movement unlocks a door before the player crosses it, so a state only counts once it differs from the last one
+fn history_system(
+    mut history: ResMut<MoveHistory>,
+    game_stats: Res<GameStats>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    mut cell_entered: EventReader<CellEntered>,
+    mut door_unlocked: EventReader<DoorUnlocked>
+){
+    let walked = history.path.len();
+    history.path.extend(cell_entered.iter().map(|cell| cell.position));
+    let moved = history.path.len() > walked || door_unlocked.iter().count() > 0;
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    if history.entries.is_empty() || (moved && history.entries.last().map(|entry| &entry.state) != Some(&state)) {
+        let walked = history.path.len();
+        history.entries.push(HistoryEntry{ state, walked, doors_unlocked: game_stats.doors_unlocked });
+    }
+}
+
+fn undo_system(
+    actions: Res<Actions>,
+    mut history: ResMut<MoveHistory>,
+    mut game_stats: ResMut<GameStats>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    player_state: Res<PlayerState>,
+    mut demo: ResMut<Demo>,
+    mut redraw: EventWriter<Redraw>
+){
+    if !actions.just_pressed(Action::Undo) || player_state.step.is_some() || history.entries.len() < 2 {
+        return;
+    }
+    history.entries.pop();
+    let (state, walked, doors_unlocked) = match history.entries.last() {
+        Some(entry) => (entry.state.clone(), entry.walked, entry.doors_unlocked),
+        None => return
+    };
+    maze_visual_state.maze.state = Some(state);
+    history.path.truncate(walked);
+    game_stats.doors_unlocked = doors_unlocked;
+    demo.active = false;
+    // Keys and doors come back with the older state, redrawing the maze brings them back and puts the player on its field
+    redraw.send(Redraw);
+}
+
+fn timer_system(mut game_stats: ResMut<GameStats>, time: Res<Time>){
+    game_stats.elapsed += time.delta_seconds_f64();
+}
+
+fn stats_system(
+    mut game_stats: ResMut<GameStats>,
+    mut door_unlocked: EventReader<DoorUnlocked>,
+    mut door_blocked: EventReader<DoorBlocked>
+){
+    game_stats.doors_unlocked += door_unlocked.iter().count();
+    game_stats.doors_blocked += door_blocked.iter().count();
+}
+
+// No move ever enters the start cell, so a level that starts on an exit is won right away
+fn start_on_exit_system(
+    maze_visual_state: Res<MazeVisualState>,
+    mut exit_reached: EventWriter<ExitReached>
+){
+    if let Some(state) = maze_visual_state.maze.state.as_ref() {
+        if maze_visual_state.maze.exits.contains(&state.position) {
+            exit_reached.send(ExitReached);
+        }
+    }
+}
+
+fn exit_detection_system(
+    mut app_state: ResMut<State<AppState>>,
+    mut exit_reached: EventReader<ExitReached>
+){
+    if exit_reached.iter().count() > 0 {
+        app_state.push(AppState::Victory).unwrap_or_default();
+    }
+}
+
+fn victory_screen_spawn_system(
+    mut commands: Commands,
+    game_stats: Res<GameStats>,
+    history: Res<MoveHistory>,
+    maze_visual_state: Res<MazeVisualState>,
+    game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
+    bindings: Res<Bindings>
+){
+    let cells_travelled = history.path.len();
+    let initial_state = MazeState::create_from_maze(&maze_visual_state.maze);
+    let comparison = match maze_visual_state.maze.search_a_star(initial_state).path {
+        Some(path) => {
+            let optimal = path.len() - 1;
+            if cells_travelled <= optimal {
+                format!("Shortest path: {optimal} cells - you found it!")
+            }else{
+                format!("Shortest path: {optimal} cells - {} more than optimal", cells_travelled - optimal)
+            }
+        },
+        None => "Shortest path: the solver found none".to_string()
+    };
+
+    commands.spawn(NodeBundle{
+        style: Style {
+            size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+            position_type: PositionType::Absolute,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: Theme::translucent(theme.background, 217).into(),
+        z_index: ZIndex::Global(40),
+        ..default()
+    })
+    .insert(VictoryScreen)
+    .with_children(|parent|{
+        parent.spawn(TextBundle{
+            text: Text::from_section(
+                format!(
+                    "You escaped!\n\nTime: {:.2}s\nCells travelled: {}\nDoors unlocked: {}\nLocked doors bumped into: {}\n{}\n\n{} - main menu   {} - level select",
+                    game_stats.elapsed,
+                    cells_travelled,
+                    game_stats.doors_unlocked,
+                    game_stats.doors_blocked,
+                    comparison,
+                    bindings.name(Action::Confirm),
+                    bindings.name(Action::LevelSelect)
+                ),
+                TextStyle {
+                    font_size: 40.,
+                    color: Theme::color(theme.text),
+                    font: game_textures.font.clone()
+                }
+            ).with_alignment(TextAlignment::CENTER),
+            ..default()
+        });
+    });
+}
+
+fn victory_keyboard_event_system(
+    mut actions: ResMut<Actions>,
+    mut app_state: ResMut<State<AppState>>
+){
+    if actions.clear_just_pressed(Action::Confirm){
+        app_state.replace(AppState::MainMenu).unwrap_or_default();
+    }else if actions.clear_just_pressed(Action::LevelSelect){
+        app_state.replace(AppState::LevelSelect).unwrap_or_default();
+    }
+}
+
+fn victory_screen_despawn_system(
+    mut commands: Commands,
+    query: Query<Entity, With<VictoryScreen>>
+){
+    for e in query.iter(){
+        commands.entity(e).despawn_recursive();
+    }
+}
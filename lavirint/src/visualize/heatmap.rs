@@ -0,0 +1,132 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::maze::layout::hex_to_rgb;
+
+use super::{AppState, input::{Actions, Action}, maze_visual::{MazeVisualState, MazeVisual, Relayout}};
+
+const HEATMAP_NEAR_COLOR: &str = "2E8B57";
+const HEATMAP_FAR_COLOR: &str = "D9534F";
+const HEATMAP_UNREACHABLE_COLOR: &str = "5A5A5A";
+const HEATMAP_ALPHA: f32 = 0.6;
+
+pub struct HeatmapPlugin;
+
+#[derive(Component)]
+pub struct HeatmapCell{
+    pub position: (usize, usize)
+}
+
+#[derive(Resource, Default)]
+pub struct HeatmapState {
+    pub visible: bool,
+    pub keys: Option<(usize, usize)>
+}
+
+impl Plugin for HeatmapPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(HeatmapState::default())
+        .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(heatmap_reset_system))
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(heatmap_keyboard_event_system.label("heatmap-input"))
+            .with_system(heatmap_sync_system.after("heatmap-input").after("movement"))
+            .with_system(heatmap_relayout_system.after("relayout"))
+        );
+    }
+}
+
+fn heatmap_reset_system(mut heatmap_state: ResMut<HeatmapState>){
+    *heatmap_state = HeatmapState::default();
+}
+
+fn heat_color(distance: Option<usize>, max_distance: usize) -> Color{
+    let distance = match distance {
+        Some(distance) => distance,
+        None => {
+            let [r, g, b] = hex_to_rgb(HEATMAP_UNREACHABLE_COLOR);
+            return Color::rgba_u8(r, g, b, (HEATMAP_ALPHA * 255.) as u8);
+        }
+    };
+    let t = if max_distance == 0 { 0. } else { distance as f32 / max_distance as f32 };
+    let (near, far) = (hex_to_rgb(HEATMAP_NEAR_COLOR), hex_to_rgb(HEATMAP_FAR_COLOR));
+    let channel = |index: usize| (near[index] as f32 + (far[index] as f32 - near[index] as f32) * t) / 255.;
+    Color::rgba(channel(0), channel(1), channel(2), HEATMAP_ALPHA)
+}
+
+fn heatmap_keyboard_event_system(
+    mut commands: Commands,
+    actions: Res<Actions>,
+    mut heatmap_state: ResMut<HeatmapState>,
+    maze_visual_state: Res<MazeVisualState>,
+    query: Query<Entity, With<HeatmapCell>>
+){
+    if !actions.just_pressed(Action::Distances){
+        return;
+    }
+    heatmap_state.visible = !heatmap_state.visible;
+    if !heatmap_state.visible{
+        for e in query.iter(){
+            commands.entity(e).despawn();
+        }
+        return;
+    }
+
+    // The sync system paints the cells as soon as they exist
+    heatmap_state.keys = None;
+    for (y, row) in maze_visual_state.maze.fields.iter().enumerate(){
+        for x in 0..row.len(){
+            commands.spawn(SpriteBundle{
+                sprite: Sprite{
+                    color: heat_color(None, 0),
+                    custom_size: Some(maze_visual_state.field_dimensions),
+                    anchor: Anchor::Center,
+                    ..default()
+                },
+                transform: Transform::from_translation(maze_visual_state.field_center((y, x)).extend(2.5)),
+                ..default()
+            })
+            .insert(HeatmapCell{ position: (y, x) })
+            .insert(MazeVisual);
+        }
+    }
+}
+
+// Picking up a key or opening a door changes every distance, so the field follows the live state
+fn heatmap_sync_system(
+    mut heatmap_state: ResMut<HeatmapState>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    mut query: Query<(&HeatmapCell, &mut Sprite)>
+){
+    if !heatmap_state.visible || query.is_empty(){
+        return;
+    }
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    let keys = (state.keys, state.keys_set.len());
+    if heatmap_state.keys == Some(keys){
+        return;
+    }
+    heatmap_state.keys = Some(keys);
+    let field = maze_visual_state.maze.distance_field_from(&state);
+
+    // Fields the player can only get to with a different number of keys show their best case
+    let max_distance = field.max_distance();
+    let best = field.best();
+    for (cell, mut sprite) in query.iter_mut(){
+        let distance = field.get(cell.position, state.keys).or_else(|| best.get(&cell.position).copied());
+        sprite.color = heat_color(distance, max_distance);
+    }
+}
+
+fn heatmap_relayout_system(
+    mut relayout: EventReader<Relayout>,
+    maze_visual_state: Res<MazeVisualState>,
+    mut query: Query<(&HeatmapCell, &mut Transform, &mut Sprite)>
+){
+    if relayout.iter().count() == 0 {
+        return;
+    }
+    for (cell, mut transform, mut sprite) in query.iter_mut(){
+        transform.translation = maze_visual_state.field_center(cell.position).extend(2.5);
+        sprite.custom_size = Some(maze_visual_state.field_dimensions);
+    }
+}
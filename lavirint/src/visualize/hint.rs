@@ -0,0 +1,139 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::maze::distance::DistanceField;
+
+use super::{AppState, GameTextures, config::Theme, input::{Actions, Action}, maze_visual::{MazeVisualState, MazeVisual, Relayout}};
+
+pub struct HintPlugin;
+
+#[derive(Component)]
+pub struct HintMarker;
+
+#[derive(Resource, Default)]
+pub struct HintState {
+    // Worked out from the start of the level the first time a hint is asked for
+    pub field: Option<DistanceField>,
+    pub shown_from: Option<(usize, usize)>
+}
+
+impl Plugin for HintPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(HintState::default())
+        .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(hint_reset_system))
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(hint_keyboard_event_system.label("hint-input"))
+            .with_system(hint_expiry_system.after("movement").after("hint-input"))
+            .with_system(hint_relayout_system.after("relayout"))
+        );
+    }
+}
+
+fn hint_reset_system(mut hint_state: ResMut<HintState>){
+    *hint_state = HintState::default();
+}
+
+fn spawn_marker(commands: &mut Commands, maze_visual_state: &MazeVisualState, position: (usize, usize), color: Color){
+    let center = maze_visual_state.field_center(position);
+    commands.spawn(SpriteBundle{
+        sprite: Sprite{
+            color,
+            custom_size: Some(maze_visual_state.field_dimensions),
+            anchor: Anchor::Center,
+            ..default()
+        },
+        transform: Transform::from_translation(center.extend(2.)),
+        ..default()
+    })
+    .insert(HintMarker)
+    .insert(MazeVisual);
+}
+
+fn hint_keyboard_event_system(
+    mut commands: Commands,
+    actions: Res<Actions>,
+    game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
+    mut hint_state: ResMut<HintState>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    query: Query<Entity, With<HintMarker>>
+){
+    if !actions.just_pressed(Action::Hint){
+        return;
+    }
+    for e in query.iter(){
+        commands.entity(e).despawn();
+    }
+
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    let maze = &maze_visual_state.maze;
+    let hint = hint_state.field.get_or_insert_with(|| maze.distance_field()).hint(maze, &state);
+    hint_state.shown_from = Some(state.position);
+
+    let message = match hint {
+        Some(hint) => {
+            if let Some(next) = hint.next {
+                spawn_marker(&mut commands, &maze_visual_state, next, Theme::color(theme.solution));
+            }
+            if let Some(key) = hint.next_key {
+                spawn_marker(&mut commands, &maze_visual_state, key, Theme::translucent(theme.key, 128));
+            }
+            format!("   Hint: {} moves left", hint.moves)
+        },
+        None => "   Hint: there's no way out from here...".to_string()
+    };
+
+    commands.spawn(TextBundle{
+        text: Text::from_section(
+            message,
+            TextStyle {
+                font_size: 30.,
+                color: Theme::color(theme.text),
+                font: game_textures.font.clone()
+            }
+        ),
+        style: Style{
+            position_type: PositionType::Absolute,
+            position: UiRect{ bottom: Val::Px(10.), left: Val::Px(10.), ..default() },
+            ..default()
+        },
+        z_index: ZIndex::Global(30),
+        ..default()
+    })
+    .insert(HintMarker)
+    .insert(MazeVisual);
+}
+
+// A hint only holds for the field it was asked from
+fn hint_expiry_system(
+    mut commands: Commands,
+    mut hint_state: ResMut<HintState>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    query: Query<Entity, With<HintMarker>>
+){
+    let shown_from = match hint_state.shown_from {
+        Some(position) => position,
+        None => return
+    };
+    if maze_visual_state.maze.get_state_mut().position != shown_from{
+        for e in query.iter(){
+            commands.entity(e).despawn();
+        }
+        hint_state.shown_from = None;
+    }
+}
+
+fn hint_relayout_system(
+    mut commands: Commands,
+    mut relayout: EventReader<Relayout>,
+    mut hint_state: ResMut<HintState>,
+    query: Query<Entity, With<HintMarker>>
+){
+    if relayout.iter().count() == 0 {
+        return;
+    }
+    for e in query.iter(){
+        commands.entity(e).despawn();
+    }
+    hint_state.shown_from = None;
+}
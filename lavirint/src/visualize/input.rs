@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{prelude::*, input::InputSystem};
+use serde::Deserialize;
+
+const STICK_DEADZONE: f32 = 0.5;
+
+pub struct ActionPlugin;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum Action{
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    ToggleSolution,
+    Hint,
+    Distances,
+    Demo,
+    Replay,
+    GridSteps,
+    Fit,
+    Minimap,
+    Pause,
+    Restart,
+    Undo,
+    // Menus only
+    Confirm,
+    Back,
+    LevelSelect,
+    MainMenu,
+    // Search replay only
+    PauseReplay,
+    SwitchSolver
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct Bindings{
+    pub keys: HashMap<Action, Vec<KeyCode>>,
+    pub buttons: HashMap<Action, Vec<GamepadButtonType>>
+}
+
+// What the player asked for this frame, whichever device it came from
+#[derive(Resource, Default)]
+pub struct Actions{
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>
+}
+
+impl Default for Bindings{
+    fn default() -> Self {
+        Self{
+            keys: HashMap::from([
+                (Action::MoveUp, vec![KeyCode::Up, KeyCode::W]),
+                (Action::MoveDown, vec![KeyCode::Down, KeyCode::S]),
+                (Action::MoveLeft, vec![KeyCode::Left, KeyCode::A]),
+                (Action::MoveRight, vec![KeyCode::Right, KeyCode::D]),
+                (Action::ToggleSolution, vec![KeyCode::X]),
+                (Action::Hint, vec![KeyCode::H]),
+                (Action::Distances, vec![KeyCode::F]),
+                (Action::Demo, vec![KeyCode::G]),
+                (Action::Replay, vec![KeyCode::V]),
+                (Action::GridSteps, vec![KeyCode::T]),
+                (Action::Fit, vec![KeyCode::Z]),
+                (Action::Minimap, vec![KeyCode::M]),
+                (Action::Pause, vec![KeyCode::P]),
+                (Action::Restart, vec![KeyCode::R]),
+                (Action::Undo, vec![KeyCode::U, KeyCode::Back]),
+                (Action::Confirm, vec![KeyCode::Return]),
+                (Action::Back, vec![KeyCode::Escape]),
+                (Action::LevelSelect, vec![KeyCode::L]),
+                (Action::MainMenu, vec![KeyCode::Q]),
+                (Action::PauseReplay, vec![KeyCode::Space]),
+                (Action::SwitchSolver, vec![KeyCode::Tab])
+            ]),
+            // A pad runs out of buttons, so actions that never share a screen share one, e.g. North is Hint while playing,
+            // LevelSelect in the menus and SwitchSolver in the search replay
+            buttons: HashMap::from([
+                (Action::MoveUp, vec![GamepadButtonType::DPadUp]),
+                (Action::MoveDown, vec![GamepadButtonType::DPadDown]),
+                (Action::MoveLeft, vec![GamepadButtonType::DPadLeft]),
+                (Action::MoveRight, vec![GamepadButtonType::DPadRight]),
+                (Action::ToggleSolution, vec![GamepadButtonType::LeftTrigger2]),
+                (Action::Hint, vec![GamepadButtonType::North]),
+                (Action::Distances, vec![GamepadButtonType::RightThumb]),
+                (Action::Demo, vec![GamepadButtonType::RightTrigger2]),
+                (Action::Replay, vec![GamepadButtonType::Mode]),
+                (Action::GridSteps, vec![GamepadButtonType::LeftThumb]),
+                (Action::Fit, vec![GamepadButtonType::RightTrigger]),
+                (Action::Minimap, vec![GamepadButtonType::LeftTrigger]),
+                (Action::Pause, vec![GamepadButtonType::Start]),
+                (Action::Restart, vec![GamepadButtonType::Select]),
+                (Action::Undo, vec![GamepadButtonType::West]),
+                (Action::Confirm, vec![GamepadButtonType::South]),
+                (Action::Back, vec![GamepadButtonType::East]),
+                (Action::LevelSelect, vec![GamepadButtonType::North]),
+                (Action::MainMenu, vec![GamepadButtonType::Select]),
+                (Action::PauseReplay, vec![GamepadButtonType::South]),
+                (Action::SwitchSolver, vec![GamepadButtonType::North])
+            ])
+        }
+    }
+}
+
+impl Bindings{
+    // How the on screen hints spell out an action, e.g. 'X' or 'U'/'Back'
+    pub fn name(&self, action: Action) -> String{
+        self.keys.get(&action)
+            .filter(|keys| !keys.is_empty())
+            .map(|keys| keys.iter().map(|key| format!("'{key:?}'")).collect::<Vec<String>>().join("/"))
+            .unwrap_or_else(|| "(unbound)".to_string())
+    }
+}
+
+impl Actions{
+    pub fn pressed(&self, action: Action) -> bool{
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool{
+        self.just_pressed.contains(&action)
+    }
+
+    // Screens sharing an action take it, so a state change doesn't hand it to the next screen in the same frame
+    pub fn clear_just_pressed(&mut self, action: Action) -> bool{
+        self.just_pressed.remove(&action)
+    }
+}
+
+impl Plugin for ActionPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(Actions::default())
+        .add_system_to_stage(CoreStage::PreUpdate, actions_system.after(InputSystem));
+    }
+}
+
+fn actions_system(
+    kb: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+    bindings: Res<Bindings>,
+    mut actions: ResMut<Actions>
+){
+    let mut pressed = HashSet::new();
+    for (action, keys) in bindings.keys.iter(){
+        if kb.any_pressed(keys.iter().copied()) {
+            pressed.insert(*action);
+        }
+    }
+    for gamepad in gamepads.iter(){
+        for (action, button_types) in bindings.buttons.iter(){
+            if button_types.iter().any(|button_type| buttons.pressed(GamepadButton::new(gamepad, *button_type))) {
+                pressed.insert(*action);
+            }
+        }
+        let stick = |axis_type: GamepadAxisType| axes.get(GamepadAxis::new(gamepad, axis_type)).unwrap_or(0.);
+        let (x, y) = (stick(GamepadAxisType::LeftStickX), stick(GamepadAxisType::LeftStickY));
+        for (tilted, action) in [(x < -STICK_DEADZONE, Action::MoveLeft), (x > STICK_DEADZONE, Action::MoveRight), (y > STICK_DEADZONE, Action::MoveUp), (y < -STICK_DEADZONE, Action::MoveDown)]{
+            if tilted {
+                pressed.insert(action);
+            }
+        }
+    }
+    // The stick has no presses of its own, so an action starts on the first frame any of its inputs is held
+    actions.just_pressed = pressed.difference(&actions.pressed).copied().collect();
+    actions.pressed = pressed;
+}
@@ -1,259 +1,324 @@
-use bevy::{prelude::*, sprite::Anchor};
+use std::{collections::HashMap, marker::PhantomData};
 
-use crate::maze::Maze;
+use bevy::{prelude::*, ecs::system::SystemParam, sprite::Anchor, render::{render_resource::{Extent3d, TextureDimension, TextureFormat}, texture::ImageSampler}};
 
-use super::{WinSize, GameTextures, WALL_SCALE, DOOR_SCALE, KEY_SCALE, DOOR_COLOR, FIELD_COLOR, WALL_COLOR, SOLUTION_FIELD_COLOR};
+use crate::maze::{Maze, solver::Solver, state::State as MazeState, layout::{Layout, Rect as LayoutRect}, raster::Image as Raster};
+
+use super::{AppState, WinSize, GameTextures, MIN_FIELD_SIZE, config::Theme, input::{Actions, Action, Bindings}};
+
+const CHUNK_FIELDS: usize = 16;
+// One texel per world unit, the raster keeps even the thinnest wall at least a texel wide
+const CHUNK_TEXELS: f32 = 1.;
+const MAX_CHUNK_TEXTURE: f32 = 4096.;
 
 pub struct MazeVisualPlugin{
-    pub maze_instance: Option<Maze>
+    pub maze_instance: Option<Maze>,
+    pub solver: Solver
 }
 
 #[derive(Component)]
 pub struct MazeVisual;
 
 #[derive(Component)]
-pub struct Field;
+pub struct Solution;
 
+// Everything drawn from the maze layout, respawned when the window size changes
 #[derive(Component)]
-pub struct Collidable;
+pub struct MazeGeometry;
 
-#[derive(Component)]
-pub struct Solution;
+// Sent after the maze was laid out again, field_center gives the new positions
+pub struct Relayout;
 
-#[derive(Component)]
-pub enum CollidableType{
-    Wall,
-    Door,
-    Key,
-    Exit,
-    Field
+// Asks for the maze to be drawn again from the live state, even though the window didn't change
+pub struct Redraw;
+
+type GeometryQuery<'w, 's> = Query<'w, 's, Entity, Or<(With<MazeGeometry>, With<Solution>)>>;
+
+// What the maze geometry is drawn with
+#[derive(SystemParam)]
+pub struct Drawing<'w, 's>{
+    game_textures: Res<'w, GameTextures>,
+    theme: Res<'w, Theme>,
+    images: ResMut<'w, Assets<Image>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>
 }
 
 #[derive(Component)]
 pub struct Dimensions{
     pub x: f32,
-    pub y: f32,
-    pub z: f32
-}
-
-#[derive(Component)]
-pub struct CollidableDetails{
-    pub id: usize,
-    pub dim: Dimensions,
-    pub c_type: CollidableType,
-    pub position: (usize, usize)
+    pub y: f32
 }
 
 impl Dimensions{
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
-        Self{x, y, z}
+    pub fn new(x: f32, y: f32) -> Self {
+        Self{x, y}
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct MazeVisualState{
     pub maze: Maze,
-    pub entities: Vec<Entity>,
+    pub solver: Solver,
+    // Sprites that go away during play, doors by field and direction and keys by field
+    pub doors: HashMap<((usize, usize), usize), Entity>,
+    pub keys: HashMap<(usize, usize), Entity>,
     pub showing_solution: bool,
     pub size: Vec2,
-    pub field_dimensions: Vec2
+    pub field_dimensions: Vec2,
+    // Window area the layout was made for, the maze itself can be larger and left to the camera
+    pub fitted_to: Vec2
 }
 
-impl Default for MazeVisualState {
-	fn default() -> Self {
-        Self {
-            maze: match Maze::parse_from_file(&"primer.bin".to_string()){
-                Ok(v) => v,
-                Err(_)=> Maze::default()
-            },
-            entities: Vec::<Entity>::new(),
-            showing_solution: false,
-            size: Vec2::default(),
-            field_dimensions: Vec2::default()
+impl MazeVisualState{
+    pub fn field_center(&self, position: (usize, usize)) -> Vec2{
+        let (w, h) = (self.field_dimensions.x, self.field_dimensions.y);
+        Vec2::new(
+            position.1 as f32 * w - self.size.x / 2. + w / 2.,
+            self.size.y / 2. - position.0 as f32 * h - h / 2.
+        )
+    }
+
+    pub fn field_at(&self, point: Vec2) -> Option<(usize, usize)>{
+        let (w, h) = (self.field_dimensions.x, self.field_dimensions.y);
+        let (x, y) = (point.x + self.size.x / 2., self.size.y / 2. - point.y);
+        if w <= 0. || h <= 0. || x < 0. || y < 0. {
+            return None;
         }
-	}
-}
+        let (row, col) = ((y / h) as usize, (x / w) as usize);
+        (row < self.maze.dimensions.0 && col < self.maze.dimensions.1).then_some((row, col))
+    }
 
-impl MazeVisualState{
-    fn from_maze(maze: Option<Maze>) -> Self{
-        if maze.is_some(){
-            Self{
-                maze: maze.unwrap(),
-                ..default()
-            }
-        }else{
-            Self::default()
+    fn fit(&mut self, win_size: &WinSize){
+        let frame_size = win_size.frame_size;
+        let dimensions = Vec2::new(self.maze.dimensions.1 as f32, self.maze.dimensions.0 as f32);
+        self.fitted_to = Vec2::new(win_size.w - 2. * frame_size, win_size.h - 2. * frame_size);
+        self.field_dimensions = (self.fitted_to / dimensions).max(Vec2::splat(MIN_FIELD_SIZE));
+        self.size = self.field_dimensions * dimensions;
+    }
+
+    fn from_maze(maze: Option<Maze>, solver: Solver) -> Self{
+        Self{
+            maze: maze.unwrap_or_default(),
+            solver,
+            ..default()
         }
     }
 }
 
 impl Plugin for MazeVisualPlugin{
     fn build(&self, app:&mut App){
-        app.insert_resource(MazeVisualState::from_maze(self.maze_instance.clone()))
-        .add_startup_system_to_stage(StartupStage::PostStartup, labyrinth_spawn_system.label("labyrinth-spawn"))
-        //.add_startup_system_to_stage(StartupStage::PostStartup, solution_system.after("labyrinth-spawn"))
-        .add_system(keyboard_event_system);
+        app.insert_resource(MazeVisualState::from_maze(self.maze_instance.clone(), self.solver))
+        .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(labyrinth_spawn_system.label("labyrinth-spawn")))
+        .add_event::<Relayout>()
+        .add_event::<Redraw>()
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(keyboard_event_system)
+            .with_system(labyrinth_relayout_system.label("relayout"))
+        )
+        .add_system_set(SystemSet::on_exit(AppState::Playing).with_system(labyrinth_despawn_system));
     }
 }
 
+fn labyrinth_despawn_system(
+    mut commands: Commands,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    query: Query<Entity, With<MazeVisual>>
+){
+    for e in query.iter(){
+        commands.entity(e).despawn_recursive();
+    }
+    maze_visual_state.doors.clear();
+    maze_visual_state.keys.clear();
+    maze_visual_state.showing_solution = false;
+}
+
 fn labyrinth_spawn_system(
 	mut commands: Commands,
     mut maze_visual_state: ResMut<MazeVisualState>,
     game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
+    bindings: Res<Bindings>,
+    mut images: ResMut<Assets<Image>>,
 	win_size: Res<WinSize>,
 ) {
-    let frame_size = win_size.frame_size;
+    let initial_state = MazeState::create_from_maze(&maze_visual_state.maze);
+    maze_visual_state.maze.state = Some(initial_state);
+    maze_visual_state.showing_solution = false;
 
-    maze_visual_state.size = Vec2::new(win_size.w - 2. * frame_size, win_size.h - 2. * frame_size);
-    maze_visual_state.field_dimensions = Vec2::new(maze_visual_state.size.x / maze_visual_state.maze.dimensions.1 as f32, maze_visual_state.size.y / maze_visual_state.maze.dimensions.0 as f32);
+    maze_visual_state.fit(&win_size);
+    spawn_geometry(&mut commands, &mut images, &mut maze_visual_state, &game_textures, &theme);
 
-    let (start_w, start_h) = (maze_visual_state.size.x, maze_visual_state.size.y);
+    commands.spawn_empty()
+    .insert(MazeVisual)
+    .insert(TextBundle{
+        text: Text::from_section(
+            format!(
+                "   Press {} for the solution :D   {} for a hint   {} for distances   {} for a demo   {} to watch the search   Click a field to walk there   {} for grid steps   {} to fit the maze   {} for the minimap   {} to undo a move   {} to restart",
+                bindings.name(Action::ToggleSolution), bindings.name(Action::Hint), bindings.name(Action::Distances), bindings.name(Action::Demo),
+                bindings.name(Action::Replay), bindings.name(Action::GridSteps), bindings.name(Action::Fit), bindings.name(Action::Minimap),
+                bindings.name(Action::Undo), bindings.name(Action::Restart)
+            ),
+            TextStyle {
+                font_size: 30.,
+                color: Theme::color(theme.text),
+                font: game_textures.font.clone()
+            }
+        ),
+        z_index: ZIndex::Global(30),
+        ..default()
+    });
+}
+
+fn labyrinth_relayout_system(
+	mut commands: Commands,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    mut drawing: Drawing,
+	win_size: Res<WinSize>,
+    mut relayout: EventWriter<Relayout>,
+    mut redraw: EventReader<Redraw>,
+    query: GeometryQuery
+){
+    let size = Vec2::new(win_size.w - 2. * win_size.frame_size, win_size.h - 2. * win_size.frame_size);
+    let redraw = redraw.iter().count() > 0;
+    if size == maze_visual_state.fitted_to && !redraw {
+        return;
+    }
+    for e in query.iter(){
+        commands.entity(e).despawn();
+    }
+    maze_visual_state.fit(&win_size);
+    spawn_geometry(&mut commands, &mut drawing.images, &mut maze_visual_state, &drawing.game_textures, &drawing.theme);
+    relayout.send(Relayout);
+    if maze_visual_state.showing_solution {
+        solution_system(commands, drawing.game_textures, drawing.theme, maze_visual_state);
+    }
+}
+
+// Floors and walls never change during a level, so each chunk of fields is one floor sprite and one wall texture
+fn spawn_chunks(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    maze_visual_state: &MazeVisualState,
+    theme: &Theme
+) -> Vec<Entity>{
+    let maze = &maze_visual_state.maze;
     let (w, h) = (maze_visual_state.field_dimensions.x, maze_visual_state.field_dimensions.y);
+    let (start_w, start_h) = (maze_visual_state.size.x, maze_visual_state.size.y);
+    let layout = Layout{ wall_size: w.min(h) * theme.wall_scale, ..Layout::new(w, h) };
+    let wall_color = theme.wall;
+    let mut chunks = Vec::new();
+
+    for top in (0..maze.dimensions.0).step_by(CHUNK_FIELDS){
+        for left in (0..maze.dimensions.1).step_by(CHUNK_FIELDS){
+            let (bottom, right) = ((top + CHUNK_FIELDS).min(maze.dimensions.0), (left + CHUNK_FIELDS).min(maze.dimensions.1));
+            let origin = layout.field_rect((top, left));
+            let size = Vec2::new((right - left) as f32 * w, (bottom - top) as f32 * h);
+            let texels = CHUNK_TEXELS.min(MAX_CHUNK_TEXTURE / size.x.max(size.y));
+            let (texture_w, texture_h) = ((size.x * texels).ceil().max(1.) as u32, (size.y * texels).ceil().max(1.) as u32);
+
+            // The raster only paints opaque colours, so walls go into a mask that becomes the alpha channel
+            let mut mask = Raster::new(texture_w, texture_h, [0, 0, 0]);
+            for y in top..bottom{
+                for x in left..right{
+                    for (direction, wall) in maze.fields[y][x].walls.iter().enumerate(){
+                        if *wall{
+                            let rect = layout.wall_rect((y, x), direction);
+                            mask.fill_rect(&LayoutRect{
+                                x: (rect.x - origin.x) * texels,
+                                y: (rect.y - origin.y) * texels,
+                                w: rect.w * texels,
+                                h: rect.h * texels
+                            }, [255, 255, 255]);
+                        }
+                    }
+                }
+            }
+            let pixels: Vec<u8> = mask.pixels.chunks(3)
+                .flat_map(|pixel| if pixel[0] == 0 { [0, 0, 0, 0] } else { [wall_color[0], wall_color[1], wall_color[2], 255] })
+                .collect();
+            let mut texture = Image::new(
+                Extent3d{ width: texture_w, height: texture_h, depth_or_array_layers: 1 },
+                TextureDimension::D2,
+                pixels,
+                TextureFormat::Rgba8UnormSrgb
+            );
+            texture.sampler_descriptor = ImageSampler::nearest();
+
+            let corner = Vec2::new(origin.x - start_w / 2., start_h / 2. - origin.y);
+            chunks.push(commands.spawn(SpriteBundle {
+                sprite: Sprite{
+                    color: Theme::color(theme.field),
+                    custom_size: Some(size),
+                    anchor: Anchor::TopLeft,
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(corner.extend(1.)),
+                ..Default::default()
+            }).id());
+            chunks.push(commands.spawn(SpriteBundle {
+                texture: images.add(texture),
+                sprite: Sprite{
+                    custom_size: Some(Vec2::new(texture_w as f32, texture_h as f32) / texels),
+                    anchor: Anchor::TopLeft,
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(corner.extend(6.)),
+                ..Default::default()
+            }).id());
+        }
+    }
+    chunks
+}
 
-    let key_size = (w * KEY_SCALE, h * KEY_SCALE);
+// Only keys still lying around and doors still locked in the live state get drawn
+fn spawn_geometry(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    maze_visual_state: &mut MazeVisualState,
+    game_textures: &GameTextures,
+    theme: &Theme
+){
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    let (start_w, start_h) = (maze_visual_state.size.x, maze_visual_state.size.y);
+    let (w, h) = (maze_visual_state.field_dimensions.x, maze_visual_state.field_dimensions.y);
 
-    let wall_size = w.min(h) * WALL_SCALE;
-    let wall_width = w;
-    let wall_height = h;
+    let key_size = (w * theme.key_scale, h * theme.key_scale);
 
-    let door_size = w.min(h) * DOOR_SCALE;
+    let door_size = w.min(h) * theme.door_scale;
     let door_width = w;
     let door_height = h;
 
-    let door_color = Color::hex(DOOR_COLOR).unwrap();
-    let wall_color = Color::hex(WALL_COLOR).unwrap();
-
-    let horizontal_wall = Sprite{
-        color: wall_color.clone(),
-        custom_size: Some(Vec2 { x: wall_width, y: wall_size }),
-        anchor: bevy::sprite::Anchor::TopLeft,
-        ..Default::default()
-    };
-
-    let vertical_wall = Sprite{
-        color: wall_color.clone(),
-        custom_size: Some(Vec2{ x: wall_size, y: wall_height }),
-        anchor: bevy::sprite::Anchor::TopLeft,
-        ..Default::default()
-    };
+    let door_color = Theme::color(theme.door);
 
     let horizontal_door = Sprite{
-        color: door_color.clone(),
+        color: door_color,
         custom_size: Some(Vec2{ x : door_width, y: door_size }),
         anchor: bevy::sprite::Anchor::TopLeft,
         ..Default::default()
     };
 
     let vertical_door = Sprite{
-        color: door_color.clone(),
+        color: door_color,
         custom_size: Some(Vec2{ x : door_size, y: door_height }),
         anchor: bevy::sprite::Anchor::TopLeft,
         ..Default::default()
     };
 
-    let mut children = Vec::<Entity>::new();
+    let maze = &maze_visual_state.maze;
+    let locked = |position: (usize, usize), direction: usize| match maze.get_neighbour(&position, direction) {
+        Some(neighbour) => state.doors_graph.get(&position).is_some_and(|doors| doors.contains(&neighbour)),
+        None => true
+    };
+
+    let mut children = spawn_chunks(commands, images, maze_visual_state, theme);
+    let mut doors = HashMap::new();
+    let mut keys = HashMap::new();
 
     for (y, row) in maze_visual_state.maze.fields.iter().enumerate(){
         for(x, field) in row.iter().enumerate(){
-            if field.walls[0]{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(wall_size, wall_height, 0.),
-                    c_type: CollidableType::Wall,
-                    position:(y, x)
-                };
-                children.push(
-                    commands.spawn(
-                        SpriteBundle{
-                        sprite: vertical_wall.clone(),
-                        transform: Transform::
-                            from_translation(Vec3 {
-                                x: x as f32 * w - start_w / 2.,
-                                y: start_h / 2. - y as f32 * h,
-                                z: 6.
-                        }),
-                        ..Default::default()
-                    }).insert(Collidable)
-                    .insert(cd)
-                    .id()
-                );
-            }
-            if field.walls[1]{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(wall_size, wall_height, 0.),
-                    c_type: CollidableType::Wall,
-                    position:(y, x)
-                };
-                children.push(
-                    commands.spawn(
-                        SpriteBundle{
-                            sprite: vertical_wall.clone(),
-                            transform: Transform::
-                                from_translation(Vec3 {
-                                    x: (x + 1) as f32 * w - start_w / 2. - wall_size,
-                                    y: start_h / 2. - y as f32 * h,
-                                    z: 6.
-                            }),
-                            ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
-                        .id()
-                    );
-            }
-            if field.walls[2]{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(wall_width, wall_size, 0.),
-                    c_type: CollidableType::Wall,
-                    position:(y, x)
-                };
-                children.push(
-                    commands.spawn(
-                        SpriteBundle{
-                            sprite: horizontal_wall.clone(),
-                            transform: Transform::
-                                from_translation(Vec3 {
-                                    x: x as f32 * w - start_w / 2.,
-                                    y: start_h / 2. - y as f32 * h,
-                                    z: 6.
-                            }),
-                            ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
-                        .id()
-                    );
-            }
-            if field.walls[3]{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(wall_width, wall_size, 0.),
-                    c_type: CollidableType::Wall,
-                    position:(y, x)
-                };
-                children.push(
-                    commands.spawn(
-                        SpriteBundle{
-                            sprite: horizontal_wall.clone(),
-                            transform: Transform::
-                                from_translation(Vec3 {
-                                    x: x as f32 * w - start_w / 2.,
-                                    y: start_h / 2. + wall_size - (y + 1) as f32 * h ,
-                                    z: 6.
-                            }),
-                            ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
-                        .id()
-                    );
-            }
-            if field.doors[0]{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(door_size, door_height, 0.),
-                    c_type: CollidableType::Door,
-                    position:(y, x)
-                };
+            if field.doors[0] && locked((y, x), 0){
                 children.push(
                     commands.spawn(
                         SpriteBundle{
@@ -265,18 +330,12 @@ fn labyrinth_spawn_system(
                                     z: 5.
                             }),
                             ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
+                        })
                         .id()
                     );
+                doors.insert(((y, x), 0), *children.last().unwrap());
             }
-            if field.doors[1]{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(door_size, door_height, 0.),
-                    c_type: CollidableType::Door,
-                    position:(y, x)
-                };
+            if field.doors[1] && locked((y, x), 1){
                 children.push(
                     commands.spawn(
                         SpriteBundle{
@@ -288,18 +347,12 @@ fn labyrinth_spawn_system(
                                     z: 5.
                             }),
                             ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
+                        })
                         .id()
                     );
+                doors.insert(((y, x), 1), *children.last().unwrap());
             }
-            if field.doors[2]{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(door_width, door_size, 0.),
-                    c_type: CollidableType::Door,
-                    position:(y, x)
-                };
+            if field.doors[2] && locked((y, x), 2){
                 children.push(
                     commands.spawn(
                     SpriteBundle{
@@ -311,18 +364,12 @@ fn labyrinth_spawn_system(
                                     z: 5.
                             }),
                             ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
+                        })
                         .id()
                     );
+                doors.insert(((y, x), 2), *children.last().unwrap());
             }
-            if field.doors[3]{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(door_width, door_size, 0.),
-                    c_type: CollidableType::Door,
-                    position:(y, x)
-                };
+            if field.doors[3] && locked((y, x), 3){
                 children.push(
                     commands.spawn(
                         SpriteBundle{
@@ -334,19 +381,13 @@ fn labyrinth_spawn_system(
                                     z: 5.
                             }),
                             ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
+                        })
                         .id()
                     );
+                doors.insert(((y, x), 3), *children.last().unwrap());
             }
 
-            if field.key{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(key_size.0, key_size.1, 0.),
-                    c_type: CollidableType::Key,
-                    position:(y, x)
-                };
+            if field.key && state.keys_set.contains(&(y, x)){
                 children.push(
                     commands.spawn(
                         SpriteBundle {
@@ -362,19 +403,13 @@ fn labyrinth_spawn_system(
                                 z: 5.
                             }),
                             ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
+                        })
                         .id()
                     );
+                keys.insert((y, x), *children.last().unwrap());
             }
 
             if field.exit{
-                let cd = CollidableDetails{
-                    id: children.len(),
-                    dim: Dimensions::new(key_size.0, key_size.1, 0.),
-                    c_type: CollidableType::Exit,
-                    position:(y, x)
-                };
                 children.push(
                     commands.spawn(SpriteBundle {
                             texture: game_textures.exit.clone(),
@@ -389,72 +424,38 @@ fn labyrinth_spawn_system(
                                 z: 3.
                             }),
                             ..Default::default()
-                        }).insert(Collidable)
-                        .insert(cd)
+                        })
                         .id()
                     );
             }
-
-            children.push(commands.spawn(SpriteBundle {
-                sprite: Sprite{
-                    color: Color::hex(FIELD_COLOR).unwrap(),
-                    custom_size: Some(Vec2::new(w, h)),
-                    anchor: Anchor::Center,
-                    ..Default::default()
-                },
-                transform: Transform::from_translation(Vec3 {
-                    x: x as f32 * w - start_w / 2. + w/2.,
-                    y: start_h / 2. - y as f32 * h - h/2.,
-                    z: 1.
-                }),
-                ..Default::default()
-            })
-            .insert(Collidable)
-                .insert(CollidableDetails{
-                    id: children.len(),
-                    dim:Dimensions::new(w * 0.1, h * 0.1, 0.),
-                    c_type: CollidableType::Field,
-                    position:(y, x)
-                })
-                .id()
-        );
         }
     }
-    maze_visual_state.entities = children;
-
-    commands.spawn_empty()
-    .insert(TextBundle{
-        text: Text::from_section(
-            "   Press 'S' for the solution :D",
-            TextStyle {
-                font_size: 30.,
-                color: Color::rgb(0.,0.,0.),
-                font: game_textures.font.clone()
-            }
-        ),
-        z_index: ZIndex::Global(30),
-        ..default()
-    });
+    for e in children.iter(){
+        commands.entity(*e).insert(MazeVisual).insert(MazeGeometry);
+    }
+    maze_visual_state.doors = doors;
+    maze_visual_state.keys = keys;
 }
 
 fn solution_system(
     mut commands: Commands,
     game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
     mut maze_visual_state: ResMut<MazeVisualState>) -> Vec<Entity>
     {
     let (w, h) = (maze_visual_state.field_dimensions.x, maze_visual_state.field_dimensions.y);
     let (start_w, start_h) = (maze_visual_state.size.x, maze_visual_state.size.y);
     let sol_sprite = Sprite{
-        color: Color::hex(SOLUTION_FIELD_COLOR).unwrap(),
+        color: Theme::color(theme.solution),
         custom_size: Some(Vec2::new(w, h)),
         anchor: Anchor::Center,
         ..default()
     };
     let state = maze_visual_state.maze.get_state_mut().clone();
-    let solution = maze_visual_state.maze.search_for_shortest_path_parallel(state);
+    let solution = maze_visual_state.solver.solve(&maze_visual_state.maze, state);
     let mut spawned = Vec::new();
-    if solution.is_some(){
-        for step in solution.unwrap(){
+    if let Some(solution) = solution {
+        for step in solution{
             spawned.push(commands.spawn(SpriteBundle{
                 transform: Transform::from_translation(Vec3::new(
                     step.1 as f32 * w - (start_w - w) / 2.,
@@ -467,7 +468,8 @@ fn solution_system(
             .insert(Visibility{
                 is_visible: maze_visual_state.showing_solution
             })
-            .insert(Solution).id());
+            .insert(Solution)
+            .insert(MazeVisual).id());
         }
     }else{
         spawned.push(commands.spawn_empty()
@@ -476,7 +478,7 @@ fn solution_system(
                 "   No solution found for this maze...",
                 TextStyle {
                     font_size: 30.,
-                    color: Color::rgb(0.,0.,0.),
+                    color: Theme::color(theme.text),
                     font: game_textures.font.clone()
                 }
             ),
@@ -485,23 +487,25 @@ fn solution_system(
         }).insert(Visibility{
             is_visible: maze_visual_state.showing_solution
         })
-        .insert(Solution).id());
+        .insert(Solution)
+        .insert(MazeVisual).id());
     }
 
-    return spawned;
+    spawned
 }
 
 fn keyboard_event_system(
     mut commands: Commands,
-    kb: Res<Input<KeyCode>>,
+    actions: Res<Actions>,
     game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
     mut maze_visual_state: ResMut<MazeVisualState>,
     query: Query<Entity, With<Solution>>
 ){
-    if kb.just_pressed(KeyCode::S){
+    if actions.just_pressed(Action::ToggleSolution){
         maze_visual_state.showing_solution = !maze_visual_state.showing_solution;
         if maze_visual_state.showing_solution{
-            solution_system(commands, game_textures, maze_visual_state);
+            solution_system(commands, game_textures, theme, maze_visual_state);
         }else{
             for e in query.iter(){
                 commands.entity(e).despawn();
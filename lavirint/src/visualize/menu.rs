@@ -0,0 +1,255 @@
+use bevy::prelude::*;
+
+use crate::{maze::{Maze, difficulty::Difficulty}, utilities::{list_maze_files, read_visibility_radius}};
+
+use super::{AppState, GameTextures, LEVELS_DIR, config::Theme, input::{Actions, Action, Bindings}, fog::Fog, maze_visual::MazeVisualState};
+
+pub struct MenuPlugin;
+
+#[derive(Component)]
+pub struct MenuScreen;
+
+#[derive(Component)]
+pub struct MenuText;
+
+pub struct Level {
+    pub name: String,
+    pub maze: Maze,
+    pub difficulty: Difficulty,
+    pub visibility: Option<usize>
+}
+
+#[derive(Resource)]
+pub struct LevelPack {
+    pub levels: Vec<Level>,
+    pub selected: usize
+}
+
+impl LevelPack {
+    pub fn load(dir_path: &String) -> Self {
+        let mut levels = Vec::new();
+        match list_maze_files(dir_path) {
+            Ok(files) => {
+                for file in files {
+                    let path = file.to_string_lossy().to_string();
+                    match Maze::parse_from_path(&path) {
+                        Ok(maze) => levels.push(Level {
+                            name: file.file_stem().unwrap().to_string_lossy().to_string(),
+                            difficulty: maze.difficulty(),
+                            visibility: read_visibility_radius(&file),
+                            maze
+                        }),
+                        Err(er) => eprintln!("Skipping level '{path}': {er}")
+                    }
+                }
+            },
+            Err(er) => eprintln!("Couldn't read the levels directory '{dir_path}': {er}")
+        }
+        Self { levels, selected: 0 }
+    }
+}
+
+impl Plugin for MenuPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(LevelPack::load(&LEVELS_DIR.to_string()))
+        .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(main_menu_spawn_system))
+        .add_system_set(SystemSet::on_update(AppState::MainMenu).with_system(main_menu_keyboard_event_system))
+        .add_system_set(SystemSet::on_exit(AppState::MainMenu).with_system(menu_despawn_system))
+        .add_system_set(SystemSet::on_enter(AppState::LevelSelect).with_system(level_select_spawn_system))
+        .add_system_set(
+            SystemSet::on_update(AppState::LevelSelect)
+            .with_system(level_select_keyboard_event_system.label("level-select-input"))
+            .with_system(level_list_sync_system.after("level-select-input"))
+        )
+        .add_system_set(SystemSet::on_exit(AppState::LevelSelect).with_system(menu_despawn_system))
+        .add_system_set(SystemSet::on_update(AppState::Playing).with_system(pause_keyboard_event_system))
+        .add_system_set(SystemSet::on_enter(AppState::Paused).with_system(pause_menu_spawn_system))
+        .add_system_set(SystemSet::on_update(AppState::Paused).with_system(paused_keyboard_event_system))
+        .add_system_set(SystemSet::on_exit(AppState::Paused).with_system(menu_despawn_system));
+    }
+}
+
+fn spawn_menu_screen(commands: &mut Commands, game_textures: &GameTextures, theme: &Theme, content: String, background: Color){
+    commands.spawn(NodeBundle{
+        style: Style {
+            size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+            position_type: PositionType::Absolute,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: background.into(),
+        z_index: ZIndex::Global(50),
+        ..default()
+    })
+    .insert(MenuScreen)
+    .with_children(|parent|{
+        parent.spawn(TextBundle{
+            text: Text::from_section(
+                content,
+                TextStyle {
+                    font_size: 40.,
+                    color: Theme::color(theme.text),
+                    font: game_textures.font.clone()
+                }
+            ).with_alignment(TextAlignment::CENTER),
+            ..default()
+        })
+        .insert(MenuText);
+    });
+}
+
+fn main_menu_spawn_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
+    bindings: Res<Bindings>
+){
+    spawn_menu_screen(
+        &mut commands,
+        &game_textures,
+        &theme,
+        format!(
+            "Labyrinth\n\n{} - play\n{} - level select\n{} - quit",
+            bindings.name(Action::Confirm), bindings.name(Action::LevelSelect), bindings.name(Action::Back)
+        ),
+        Theme::color(theme.background)
+    );
+}
+
+fn main_menu_keyboard_event_system(
+    mut actions: ResMut<Actions>,
+    mut app_state: ResMut<State<AppState>>,
+    maze_visual_state: Res<MazeVisualState>,
+    mut exit: EventWriter<bevy::app::AppExit>
+){
+    if actions.clear_just_pressed(Action::Confirm){
+        if maze_visual_state.maze.fields.is_empty(){
+            app_state.set(AppState::LevelSelect).unwrap_or_default();
+        }else{
+            app_state.set(AppState::Playing).unwrap_or_default();
+        }
+    }else if actions.clear_just_pressed(Action::LevelSelect){
+        app_state.set(AppState::LevelSelect).unwrap_or_default();
+    }else if actions.clear_just_pressed(Action::Back){
+        exit.send(bevy::app::AppExit);
+    }
+}
+
+fn level_list_text(level_pack: &LevelPack, bindings: &Bindings) -> String{
+    let mut output = "Select a level\n\n".to_string();
+    if level_pack.levels.is_empty(){
+        output += format!("No levels found in '{LEVELS_DIR}'\n").as_str();
+    }
+    for (index, level) in level_pack.levels.iter().enumerate(){
+        let marker = if index == level_pack.selected { ">" } else { " " };
+        output += format!(
+            "{marker} {} - {}x{} - {}\n",
+            level.name,
+            level.maze.dimensions.0,
+            level.maze.dimensions.1,
+            level.difficulty
+        ).as_str();
+    }
+    output += format!(
+        "\n{}/{} - choose   {} - play   {} - back",
+        bindings.name(Action::MoveUp),
+        bindings.name(Action::MoveDown),
+        bindings.name(Action::Confirm),
+        bindings.name(Action::Back)
+    ).as_str();
+    output
+}
+
+fn level_select_spawn_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
+    bindings: Res<Bindings>,
+    level_pack: Res<LevelPack>
+){
+    spawn_menu_screen(&mut commands, &game_textures, &theme, level_list_text(&level_pack, &bindings), Theme::color(theme.background));
+}
+
+fn level_select_keyboard_event_system(
+    mut actions: ResMut<Actions>,
+    mut app_state: ResMut<State<AppState>>,
+    mut level_pack: ResMut<LevelPack>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    mut fog: ResMut<Fog>
+){
+    let count = level_pack.levels.len();
+    // With no levels to pick from the only way is back
+    if actions.clear_just_pressed(Action::Back){
+        app_state.set(AppState::MainMenu).unwrap_or_default();
+    }else if count > 0 && actions.just_pressed(Action::MoveUp){
+        level_pack.selected = (level_pack.selected + count - 1) % count;
+    }else if count > 0 && actions.just_pressed(Action::MoveDown){
+        level_pack.selected = (level_pack.selected + 1) % count;
+    }else if count > 0 && actions.clear_just_pressed(Action::Confirm){
+        maze_visual_state.maze = level_pack.levels[level_pack.selected].maze.clone();
+        fog.level_radius = level_pack.levels[level_pack.selected].visibility;
+        app_state.set(AppState::Playing).unwrap_or_default();
+    }
+}
+
+fn level_list_sync_system(
+    level_pack: Res<LevelPack>,
+    bindings: Res<Bindings>,
+    mut query: Query<&mut Text, With<MenuText>>
+){
+    if !level_pack.is_changed(){
+        return;
+    }
+    for mut text in query.iter_mut(){
+        text.sections[0].value = level_list_text(&level_pack, &bindings);
+    }
+}
+
+fn pause_keyboard_event_system(
+    mut actions: ResMut<Actions>,
+    mut app_state: ResMut<State<AppState>>
+){
+    // Back leaves the game for the pause menu, which Back then closes again
+    if actions.clear_just_pressed(Action::Pause) || actions.clear_just_pressed(Action::Back){
+        app_state.push(AppState::Paused).unwrap_or_default();
+    }
+}
+
+fn pause_menu_spawn_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
+    bindings: Res<Bindings>
+){
+    spawn_menu_screen(
+        &mut commands,
+        &game_textures,
+        &theme,
+        format!(
+            "Paused\n\n{}/{} - resume\n{} - main menu",
+            bindings.name(Action::Pause), bindings.name(Action::Back), bindings.name(Action::MainMenu)
+        ),
+        Theme::translucent(theme.background, 217)
+    );
+}
+
+fn paused_keyboard_event_system(
+    mut actions: ResMut<Actions>,
+    mut app_state: ResMut<State<AppState>>
+){
+    if actions.clear_just_pressed(Action::Pause) || actions.clear_just_pressed(Action::Back){
+        app_state.pop().unwrap_or_default();
+    }else if actions.clear_just_pressed(Action::MainMenu){
+        app_state.replace(AppState::MainMenu).unwrap_or_default();
+    }
+}
+
+fn menu_despawn_system(
+    mut commands: Commands,
+    query: Query<Entity, With<MenuScreen>>
+){
+    for e in query.iter(){
+        commands.entity(e).despawn_recursive();
+    }
+}
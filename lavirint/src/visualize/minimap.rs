@@ -0,0 +1,251 @@
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, ecs::system::SystemParam, render::render_resource::{Extent3d, TextureDimension, TextureFormat}};
+
+use crate::maze::{Maze, state::State as MazeState, raster::Image as Raster, layout::{hex_to_rgb, Layout, Rect as LayoutRect}};
+
+use super::{AppState, camera::CameraState, config::Theme, input::{Actions, Action}, fog::Fog, maze_visual::{MazeVisualState, MazeVisual}};
+
+const MINIMAP_SIZE: f32 = 200.;
+const MINIMAP_MARGIN: f32 = 10.;
+const COLLECTED_KEY_COLOR: &str = "A9A9A9";
+const OPENED_DOOR_COLOR: &str = "BFE6F2";
+
+pub struct MinimapPlugin;
+
+#[derive(Component)]
+pub struct Minimap;
+
+#[derive(Resource, Default)]
+pub struct MinimapState {
+    pub visible: bool,
+    pub image: Option<Handle<Image>>,
+    pub field_size: f32,
+    pub painted: Option<Painted>
+}
+
+// The picture only changes with these
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Painted{
+    pub position: (usize, usize),
+    pub keys: usize,
+    pub keys_left: usize,
+    pub locked_doors: usize,
+    pub seen: usize
+}
+
+// What the minimap is painted from and into
+#[derive(SystemParam)]
+pub struct MinimapCanvas<'w, 's>{
+    images: ResMut<'w, Assets<Image>>,
+    fog: Res<'w, Fog>,
+    theme: Res<'w, Theme>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>
+}
+
+impl Plugin for MinimapPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(MinimapState::default())
+        .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(minimap_spawn_system.after("labyrinth-spawn")))
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(minimap_keyboard_event_system.label("minimap-toggle"))
+            .with_system(minimap_click_system.label("minimap-input").before("demo-input"))
+            .with_system(minimap_sync_system.after("minimap-toggle").after("fog-sync"))
+        );
+    }
+}
+
+fn locked_doors(state: &MazeState) -> usize{
+    state.doors_graph.values().map(|doors| doors.len()).sum()
+}
+
+fn paint(maze: &Maze, state: &MazeState, fog: &Fog, theme: &Theme, field_size: f32) -> Image{
+    let layout = Layout::new(field_size, field_size);
+    let (width, height) = layout.size(maze);
+    let mut raster = Raster::new(width.ceil() as u32, height.ceil() as u32, theme.field);
+
+    for (y, row) in maze.fields.iter().enumerate(){
+        for (x, field) in row.iter().enumerate(){
+            if field.exit {
+                raster.fill_rect(&layout.field_rect((y, x)), theme.exit);
+            }
+            if field.key {
+                let color = if state.keys_set.contains(&(y, x)) { theme.key } else { hex_to_rgb(COLLECTED_KEY_COLOR) };
+                raster.fill_rect(&layout.key_rect((y, x)), color);
+            }
+        }
+    }
+    for (y, row) in maze.fields.iter().enumerate(){
+        for (x, field) in row.iter().enumerate(){
+            for (direction, wall) in field.walls.iter().enumerate(){
+                if *wall {
+                    raster.fill_rect(&layout.wall_rect((y, x), direction), theme.wall);
+                }
+            }
+        }
+    }
+    // Doors are drawn last and at least two pixels thick, at this scale they'd vanish otherwise
+    for (y, row) in maze.fields.iter().enumerate(){
+        for (x, field) in row.iter().enumerate(){
+            for (direction, door) in field.doors.iter().enumerate(){
+                if !*door {
+                    continue;
+                }
+                let opened = maze.get_neighbour(&(y, x), direction)
+                    .is_some_and(|neighbour| !state.doors_graph.get(&(y, x)).is_some_and(|doors| doors.contains(&neighbour)));
+                let rect = layout.door_rect((y, x), direction);
+                let rect = if direction < 2 {
+                    LayoutRect{ x: rect.x + rect.w / 2. - 1., w: rect.w.max(2.), ..rect }
+                }else{
+                    LayoutRect{ y: rect.y + rect.h / 2. - 1., h: rect.h.max(2.), ..rect }
+                };
+                raster.fill_rect(&rect, if opened { hex_to_rgb(OPENED_DOOR_COLOR) } else { theme.door });
+            }
+        }
+    }
+    for (y, row) in maze.fields.iter().enumerate(){
+        for x in 0..row.len(){
+            if !fog.is_seen((y, x)) {
+                raster.fill_rect(&layout.field_rect((y, x)), theme.fog);
+            }
+        }
+    }
+    raster.fill_circle(layout.field_center(state.position), (field_size * 0.4).max(1.5), theme.player);
+
+    let pixels: Vec<u8> = raster.pixels.chunks(3).flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255]).collect();
+    Image::new(
+        Extent3d{ width: raster.width, height: raster.height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb
+    )
+}
+
+fn spawn_minimap(commands: &mut Commands, canvas: &mut MinimapCanvas, minimap_state: &mut MinimapState, maze_visual_state: &mut MazeVisualState){
+    let dimensions = maze_visual_state.maze.dimensions;
+    minimap_state.field_size = (MINIMAP_SIZE / dimensions.0.max(dimensions.1) as f32).floor().max(1.);
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    let image = paint(&maze_visual_state.maze, &state, &canvas.fog, &canvas.theme, minimap_state.field_size);
+    let size = Vec2::new(image.texture_descriptor.size.width as f32, image.texture_descriptor.size.height as f32);
+    let handle = canvas.images.add(image);
+    minimap_state.image = Some(handle.clone());
+    minimap_state.painted = None;
+
+    commands.spawn(ImageBundle{
+        image: UiImage(handle),
+        style: Style{
+            size: Size::new(Val::Px(size.x), Val::Px(size.y)),
+            position_type: PositionType::Absolute,
+            position: UiRect{ top: Val::Px(MINIMAP_MARGIN), right: Val::Px(MINIMAP_MARGIN), ..default() },
+            ..default()
+        },
+        z_index: ZIndex::Global(40),
+        ..default()
+    })
+    .insert(Minimap)
+    .insert(MazeVisual);
+}
+
+// The minimap stays open from one level to the next
+fn minimap_spawn_system(
+    mut commands: Commands,
+    mut canvas: MinimapCanvas,
+    mut minimap_state: ResMut<MinimapState>,
+    mut maze_visual_state: ResMut<MazeVisualState>
+){
+    if minimap_state.visible {
+        spawn_minimap(&mut commands, &mut canvas, &mut minimap_state, &mut maze_visual_state);
+    }
+}
+
+fn minimap_keyboard_event_system(
+    mut commands: Commands,
+    actions: Res<Actions>,
+    mut canvas: MinimapCanvas,
+    mut minimap_state: ResMut<MinimapState>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    query: Query<Entity, With<Minimap>>
+){
+    if !actions.just_pressed(Action::Minimap){
+        return;
+    }
+    minimap_state.visible = !minimap_state.visible;
+    if minimap_state.visible {
+        spawn_minimap(&mut commands, &mut canvas, &mut minimap_state, &mut maze_visual_state);
+    }else{
+        for e in query.iter(){
+            commands.entity(e).despawn();
+        }
+        minimap_state.image = None;
+    }
+}
+
+fn minimap_sync_system(
+    mut canvas: MinimapCanvas,
+    mut minimap_state: ResMut<MinimapState>,
+    mut maze_visual_state: ResMut<MazeVisualState>
+){
+    let handle = match (minimap_state.visible, minimap_state.image.clone()) {
+        (true, Some(handle)) => handle,
+        _ => return
+    };
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    let painted = Painted{
+        position: state.position,
+        keys: state.keys,
+        keys_left: state.keys_set.len(),
+        locked_doors: locked_doors(&state),
+        seen: canvas.fog.seen.len()
+    };
+    if minimap_state.painted == Some(painted) && !canvas.theme.is_changed() {
+        return;
+    }
+    if let Some(image) = canvas.images.get_mut(&handle) {
+        *image = paint(&maze_visual_state.maze, &state, &canvas.fog, &canvas.theme, minimap_state.field_size);
+        minimap_state.painted = Some(painted);
+    }
+}
+
+// A click on the minimap moves the camera there instead of walking the player
+fn minimap_click_system(
+    mut mouse: ResMut<Input<MouseButton>>,
+    windows: Res<Windows>,
+    minimap_state: Res<MinimapState>,
+    maze_visual_state: Res<MazeVisualState>,
+    mut camera_state: ResMut<CameraState>,
+    mut query: Query<&mut Transform, With<Camera2d>>
+){
+    if !minimap_state.visible || !mouse.just_pressed(MouseButton::Left){
+        return;
+    }
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return
+    };
+    let cursor = match window.cursor_position() {
+        Some(cursor) => cursor,
+        None => return
+    };
+
+    let field_size = minimap_state.field_size;
+    let dimensions = maze_visual_state.maze.dimensions;
+    let size = Vec2::new(dimensions.1 as f32, dimensions.0 as f32) * field_size;
+    // Cursor positions start at the bottom left of the window, the minimap hangs from the top right
+    let corner = Vec2::new(window.width() - MINIMAP_MARGIN - size.x, window.height() - MINIMAP_MARGIN);
+    let (x, y) = (cursor.x - corner.x, corner.y - cursor.y);
+    if x < 0. || y < 0. || x >= size.x || y >= size.y {
+        return;
+    }
+    mouse.clear_just_pressed(MouseButton::Left);
+
+    let position = ((y / field_size) as usize, (x / field_size) as usize);
+    let center = maze_visual_state.field_center(position);
+    camera_state.follow = false;
+    camera_state.fitted = false;
+    if let Ok(mut transform) = query.get_single_mut() {
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+    }
+}
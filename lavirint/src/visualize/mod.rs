@@ -1,12 +1,46 @@
 use bevy::{prelude::*};
 
-use crate::maze::Maze;
+use crate::maze::{Maze, solver::Solver};
 
 mod player;
 mod maze_visual;
-
-use player::PlayerPlugin;
+mod game;
+mod menu;
+mod replay;
+mod demo;
+mod hint;
+mod heatmap;
+mod camera;
+mod minimap;
+mod fog;
+mod config;
+mod input;
+mod sound;
+
+use player::{PlayerPlugin, Movement};
 use maze_visual::MazeVisualPlugin;
+use game::GamePlugin;
+use menu::MenuPlugin;
+use replay::ReplayPlugin;
+use demo::DemoPlugin;
+use hint::HintPlugin;
+use heatmap::HeatmapPlugin;
+use camera::CameraPlugin;
+use minimap::MinimapPlugin;
+use fog::FogPlugin;
+use config::{ConfigPlugin, Theme};
+use input::ActionPlugin;
+use sound::SoundPlugin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppState {
+	MainMenu,
+	LevelSelect,
+	Playing,
+	Paused,
+	Victory,
+	Replay
+}
 
 #[derive(Resource)]
 pub struct WinSize {
@@ -16,7 +50,7 @@ pub struct WinSize {
 }
 
 #[derive(Resource)]
-struct GameTextures {
+pub struct GameTextures {
 	player: Handle<Image>,
     key: Handle<Image>,
 	font: Handle<Font>,
@@ -24,29 +58,17 @@ struct GameTextures {
 }
 const FONT_PATH: &str = "font.otf";
 
-const PLAYER_SPRITE : &str = "player.png";
-const PLAYER_SCALE: f32 = 0.65;
-const PLAYER_ASSET_DIMENSIONS: (f32, f32) = (144., 75.);
-
-const KEY_SPRITE : &str = "key.png";
-const KEY_SCALE: f32 = 0.5;
+const LEVELS_DIR: &str = "levels";
 
-const EXIT_SPRITE : &str = "exit.png";
+const PLAYER_ASSET_DIMENSIONS: (f32, f32) = (144., 75.);
 
 const FRAME_SCALE: f32 = 0.1;
-const WALL_SCALE: f32 = 0.025;
-const DOOR_SCALE: f32 = 0.05;
-
-const BASE_SPEED: f32 = 500.;
 
-const FIELD_COLOR: &str = "DBCBEA";
-const WALL_COLOR: &str = "40315D";
-const DOOR_COLOR: &str = "248BB1";
-const SOLUTION_FIELD_COLOR: &str = "9DD6EA";
+// Below this many pixels per field the maze gets bigger than the window and the camera takes over
+const MIN_FIELD_SIZE: f32 = 32.;
 
-pub fn display(maze: Option<Maze>){
+pub fn display(maze: Option<Maze>, solver: Solver, grid: bool, fog: bool, visibility: Option<usize>){
     App::new()
-    .insert_resource(ClearColor(Color::rgb(1., 1., 1.)))
     .add_plugins(DefaultPlugins.set(WindowPlugin{
         window: WindowDescriptor {
             title: "Labyrinth".to_string(),
@@ -54,8 +76,21 @@ pub fn display(maze: Option<Maze>){
     },
     ..Default::default()
     }))
-    .add_plugin(PlayerPlugin)
-    .add_plugin(MazeVisualPlugin{ maze_instance: maze })
+    .add_state(AppState::MainMenu)
+    .add_plugin(ConfigPlugin)
+    .add_plugin(ActionPlugin)
+    .add_plugin(MenuPlugin)
+    .add_plugin(PlayerPlugin{ movement: if grid { Movement::Grid } else { Movement::Free } })
+    .add_plugin(MazeVisualPlugin{ maze_instance: maze, solver })
+    .add_plugin(GamePlugin)
+    .add_plugin(ReplayPlugin)
+    .add_plugin(DemoPlugin)
+    .add_plugin(HintPlugin)
+    .add_plugin(HeatmapPlugin)
+    .add_plugin(CameraPlugin)
+    .add_plugin(MinimapPlugin)
+    .add_plugin(FogPlugin{ enabled: fog, level_radius: visibility })
+    .add_plugin(SoundPlugin)
     .add_startup_system(setup_system)
 	.add_system(window_resize_system)
     .run();
@@ -72,6 +107,7 @@ fn window_resize_system(mut windows: ResMut<Windows>, mut win_size: ResMut<WinSi
 fn setup_system(
 	mut commands: Commands,
 	asset_server: Res<AssetServer>,
+	theme: Res<Theme>,
 	mut windows: ResMut<Windows>,
 ) {
 	commands.spawn(Camera2dBundle::default());
@@ -83,10 +119,10 @@ fn setup_system(
 	commands.insert_resource(win_size);
 
 	let game_textures = GameTextures {
-		player: asset_server.load(PLAYER_SPRITE),
-        key: asset_server.load(KEY_SPRITE),
+		player: asset_server.load(theme.player_sprite.as_str()),
+        key: asset_server.load(theme.key_sprite.as_str()),
 		font: asset_server.load(FONT_PATH),
-		exit: asset_server.load(EXIT_SPRITE)
+		exit: asset_server.load(theme.exit_sprite.as_str())
 	};
 	commands.insert_resource(game_textures);
 }
\ No newline at end of file
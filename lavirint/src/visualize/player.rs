@@ -1,10 +1,22 @@
-use bevy::{prelude::*, sprite::{Anchor, collide_aabb::collide}};
+use bevy::{prelude::*, sprite::Anchor, ecs::system::SystemParam};
 
-use crate::maze::state::UnlockDoor;
+use crate::maze::state::{State as MazeState, UnlockDoor};
 
-use super::{WinSize, GameTextures, PLAYER_SCALE, maze_visual::{MazeVisualState, Collidable, CollidableType, Dimensions, CollidableDetails}, BASE_SPEED, PLAYER_ASSET_DIMENSIONS};
+use super::{AppState, GameTextures, maze_visual::{MazeVisualState, MazeVisual, Dimensions, Relayout}, PLAYER_ASSET_DIMENSIONS, config::Theme, input::{Actions, Action}};
 
-pub struct PlayerPlugin;
+const STEP_SECONDS: f32 = 0.12;
+
+pub struct PlayerPlugin{
+    pub movement: Movement
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Movement{
+    #[default]
+    Free,
+    // One field per key press, moving through State::transfer_state like the solvers do
+    Grid
+}
 
 #[derive(Component)]
 pub struct Player;
@@ -18,44 +30,138 @@ pub struct Velocity {
 #[derive(Component)]
 pub struct Inventory;
 
+pub struct KeyCollected;
+
+pub struct DoorUnlocked;
+
+pub struct DoorBlocked;
+
+pub struct CellEntered {
+    pub position: (usize, usize)
+}
+
+pub struct ExitReached;
+
+// The field a door is on and the field it leads to
+type DoorEdge = ((usize, usize), (usize, usize));
+
+#[derive(SystemParam)]
+pub struct GameplayEvents<'w, 's> {
+    key_collected: EventWriter<'w, 's, KeyCollected>,
+    door_unlocked: EventWriter<'w, 's, DoorUnlocked>,
+    door_blocked: EventWriter<'w, 's, DoorBlocked>,
+    cell_entered: EventWriter<'w, 's, CellEntered>,
+    exit_reached: EventWriter<'w, 's, ExitReached>,
+    // The door the player is pushing against, so holding a key against it is reported once
+    blocked: Local<'s, Option<DoorEdge>>
+}
+
+impl GameplayEvents<'_, '_> {
+    fn block(&mut self, from: (usize, usize), to: (usize, usize)){
+        if *self.blocked != Some((from, to)) {
+            *self.blocked = Some((from, to));
+            self.door_blocked.send(DoorBlocked);
+        }
+    }
+}
+
+pub struct GridStep {
+    pub from: Vec2,
+    pub to: Vec2,
+    pub elapsed: f32,
+    // Applied once the player arrives, so the exit isn't reached halfway through the step
+    pub state: Option<MazeState>,
+    // Announced on arrival too, when the spent key has left the state
+    pub unlocked: bool
+}
+
 #[derive(Resource)]
 pub struct PlayerState {
     pub spawned: bool,
     pub size: Vec2,
+    pub movement: Movement,
+    pub step: Option<GridStep>
 }
 
 impl Default for PlayerState {
 	fn default() -> Self {
-		Self { spawned: false, size: Vec2::new(100., 50.) }
+		Self { spawned: false, size: Vec2::new(100., 50.), movement: Movement::default(), step: None }
 	}
 }
 
 impl Plugin for PlayerPlugin{
     fn build(&self, app:&mut App){
-        app.insert_resource(PlayerState::default())
-        .add_startup_system_to_stage(StartupStage::PostStartup, player_spawn_system.label("player-spawn").after("labyrinth-spawn"))
-        .add_startup_system_to_stage(StartupStage::PostStartup, setup_inventory_system.after("player-spawn"))
-        .add_system(player_keyboard_event_system)
-        .add_system(player_movement_system.label("movement").after("player-spawn"))
-        .add_system(inventory_sync_system.after("movement"));
+        app.insert_resource(PlayerState{ movement: self.movement, ..default() })
+        .add_event::<KeyCollected>()
+        .add_event::<DoorUnlocked>()
+        .add_event::<DoorBlocked>()
+        .add_event::<CellEntered>()
+        .add_event::<ExitReached>()
+        .add_system_set(
+            SystemSet::on_enter(AppState::Playing)
+            .with_system(player_spawn_system.label("player-spawn").after("labyrinth-spawn"))
+            .with_system(setup_inventory_system.after("player-spawn"))
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Playing)
+            .with_system(movement_mode_system.before("player-input"))
+            .with_system(player_keyboard_event_system.label("player-input"))
+            .with_system(player_movement_system.label("movement").after("player-input"))
+            .with_system(player_grid_step_system.label("movement").after("player-input"))
+            .with_system(inventory_sync_system.after("movement").after("relayout"))
+            .with_system(player_relayout_system.after("relayout").before("player-input"))
+        );
     }
 }
 
+fn movement_mode_system(
+	actions: Res<Actions>,
+	mut player_state: ResMut<PlayerState>,
+	maze_visual_state: Res<MazeVisualState>,
+	query: Query<&Transform, With<Player>>
+) {
+	if !actions.just_pressed(Action::GridSteps) || player_state.step.is_some() {
+		return;
+	}
+	player_state.movement = match player_state.movement {
+		Movement::Free => Movement::Grid,
+		Movement::Grid => Movement::Free
+	};
+	// Free movement can leave the player between two fields, so grid mode starts by centring it
+	if let (Movement::Grid, Ok(transform)) = (player_state.movement, query.get_single()) {
+		let position = maze_visual_state.maze.state.as_ref().map(|state| state.position).unwrap_or(maze_visual_state.maze.start);
+		player_state.step = Some(GridStep{
+			from: transform.translation.truncate(),
+			to: maze_visual_state.field_center(position),
+			elapsed: 0.,
+			state: None,
+			unlocked: false
+		});
+	}
+}
+
 fn player_keyboard_event_system(
-	kb: Res<Input<KeyCode>>,
+	actions: Res<Actions>,
+	player_state: Res<PlayerState>,
 	mut query: Query<&mut Velocity, With<Player>>,
 ) {
 	if let Ok(mut velocity) = query.get_single_mut() {
-		velocity.x = if kb.pressed(KeyCode::Left) {
+		// Grid steps read the key presses themselves, anything left in the velocity comes from the demo walker
+		if player_state.movement == Movement::Grid {
+			velocity.x = 0.;
+			velocity.y = 0.;
+			return;
+		}
+		velocity.x = if actions.pressed(Action::MoveLeft) {
 			-1.
-		} else if kb.pressed(KeyCode::Right) {
+		} else if actions.pressed(Action::MoveRight) {
 			1.
 		} else {
 			0.
 		};
-        velocity.y = if kb.pressed(KeyCode::Up){
+        velocity.y = if actions.pressed(Action::MoveUp){
             1.
-        } else if kb.pressed(KeyCode::Down){
+        } else if actions.pressed(Action::MoveDown){
             -1.
         }else{
             0.
@@ -67,19 +173,18 @@ fn player_spawn_system(
 	mut commands: Commands,
 	mut player_state: ResMut<PlayerState>,
 	game_textures: Res<GameTextures>,
-	win_size: Res<WinSize>,
+	theme: Res<Theme>,
     maze_visual_state: Res<MazeVisualState>
 ) {
-    let (w, h) = (maze_visual_state.field_dimensions.x, maze_visual_state.field_dimensions.y);
-    let (start_x, start_y) = (maze_visual_state.maze.start.1 as f32 * w, maze_visual_state.maze.start.0 as f32 * h);
     let p_size = PLAYER_ASSET_DIMENSIONS;//assets.get(&game_textures.player).un
-    player_state.size = Vec2::new(p_size.0 * PLAYER_SCALE, p_size.1 * PLAYER_SCALE);
-    let pos = (start_x - win_size.w / 2. + win_size.frame_size + w/2., win_size.h / 2. - win_size.frame_size - start_y - h/2.);
+    player_state.size = Vec2::new(p_size.0 * theme.player_scale, p_size.1 * theme.player_scale);
+    let start = maze_visual_state.field_center(maze_visual_state.maze.start);
+    let pos = (start.x, start.y);
         commands
             .spawn(SpriteBundle {
                 texture: game_textures.player.clone(),
                 sprite: Sprite{
-                    custom_size:Some(player_state.size.clone()),
+                    custom_size:Some(player_state.size),
                     anchor: Anchor::Center,
                     ..default()
                 },
@@ -94,154 +199,279 @@ fn player_spawn_system(
                 ..default()
             })
             .insert(Player)
+            .insert(MazeVisual)
             .insert(Velocity { x: 0., y: 0. })
-            .insert(Dimensions::new(player_state.size.x, player_state.size.y, 0.));
+            .insert(Dimensions::new(player_state.size.x, player_state.size.y));
 
     player_state.spawned = true;
+    player_state.step = None;
 }
 
-fn player_movement_system(
-    mut commands: Commands,
-	mut player: Query<(&Velocity, &mut Transform, &Dimensions), With<Player>>,
-    mut maze_visual_state: ResMut<MazeVisualState>,
-    coll_query: Query<(&Transform, &CollidableDetails), (With<Collidable>, Without<Player>)>,
-    time: Res<Time>
-) {
-    if let Ok((velocity, mut transform, player_dim)) = player.get_single_mut(){
-        let translation = &mut transform.translation;
-
-        let dx = velocity.x * time.delta_seconds() * BASE_SPEED;
-        let dy = velocity.y * time.delta_seconds() * BASE_SPEED;
-
-        let mut flag_x= true;
-        let mut flag_y= true;
-
-        let x_pos = Vec3::new(translation.x + dx, translation.y,0.);
-        let y_pos = Vec3::new(translation.x, translation.y + dy,0.);
-        let player_dim = Vec2::new(player_dim.x, player_dim.y);
-
-        for (trans, metadata) in coll_query.iter(){
-            let coll_pos = Vec3::new(trans.translation.x + metadata.dim.x / 2., trans.translation.y - metadata.dim.y / 2., 0.);
-            let coll_dim = Vec2::new(metadata.dim.x, metadata.dim.y);
-            if flag_x && collide(
-                coll_pos,
-                coll_dim,
-                x_pos,
-                player_dim * 0.9).is_some(){
-                flag_x = match metadata.c_type{
-                    CollidableType::Wall => false,
-                    CollidableType::Door => {
-                        let my_position = maze_visual_state.maze.get_state_mut().position.clone();
-                        let door_position = (my_position.0, (my_position.1 as i32 + velocity.x.signum() as i32) as usize);
-                        match maze_visual_state.maze.get_state_mut().unlock_door(&door_position){
-                            UnlockDoor::Unlocked => {
-                                commands.entity(maze_visual_state.entities[metadata.id]).despawn();
-                                true
-                            },
-                            _ => {
-                                false
-                            }
+// Everything entering the live state's field caused, the state has already moved there
+fn announce_arrival(
+    commands: &mut Commands,
+    events: &mut GameplayEvents,
+    maze_visual_state: &mut MazeVisualState,
+    collected: bool
+){
+    let position = maze_visual_state.maze.get_state_mut().position;
+    events.cell_entered.send(CellEntered{ position });
+    if collected {
+        if let Some(key) = maze_visual_state.keys.remove(&position){
+            commands.entity(key).despawn();
+        }
+        events.key_collected.send(KeyCollected);
+    }
+    if maze_visual_state.maze.exits.contains(&position) {
+        events.exit_reached.send(ExitReached);
+    }
+}
+
+// Moves the player along one axis inside the field it stands on, only looking at that field's walls and doors.
+// Crossing a side into the neighbouring field is what moves the logical position, picks up keys and opens doors.
+fn step_axis(
+    commands: &mut Commands,
+    events: &mut GameplayEvents,
+    maze_visual_state: &mut MazeVisualState,
+    translation: &mut Vec3,
+    half: Vec2,
+    delta: f32,
+    horizontal: bool
+){
+    let position = maze_visual_state.maze.get_state_mut().position;
+    let center = maze_visual_state.field_center(position);
+    let (w, h) = (maze_visual_state.field_dimensions.x, maze_visual_state.field_dimensions.y);
+    let (along, across, span, across_span, half_along, half_across, center_along, center_across) = if horizontal {
+        (translation.x, translation.y, w, h, half.x, half.y, center.x, center.y)
+    }else{
+        (translation.y, translation.x, h, w, half.y, half.x, center.y, center.x)
+    };
+    // World y grows upwards while rows grow downwards
+    let direction = match (horizontal, delta < 0.) {
+        (true, true) => 0,
+        (true, false) => 1,
+        (false, true) => 3,
+        (false, false) => 2
+    };
+    let sign = delta.signum();
+    let limit = center_along + sign * (span / 2. - half_along);
+    let target = along + delta;
+
+    let mut new_along = target;
+    let mut new_across = across;
+    if (target - limit) * sign > 0. {
+        let open = !maze_visual_state.maze.fields[position.0][position.1].walls[direction];
+        let next = maze_visual_state.maze.get_neighbour(&position, direction).filter(|_| open);
+        let offset = across - center_across;
+        let aligned = offset.abs() <= (across_span / 2. - half_across).max(0.);
+        new_along = if sign > 0. { limit.max(along) } else { limit.min(along) };
+
+        if let Some(next) = next {
+            if !aligned {
+                // Sliding towards the middle of the field lets the player round corners without pixel-perfect steering
+                new_across = across - offset.signum() * delta.abs().min(offset.abs());
+            }else{
+                let state = maze_visual_state.maze.get_state_mut();
+                let passable = match state.unlock_door(&next) {
+                    UnlockDoor::Unlocked => {
+                        if let Some(door) = maze_visual_state.doors.remove(&(position, direction)){
+                            commands.entity(door).despawn();
                         }
-                    },
-                    CollidableType::Key => {
-                        maze_visual_state.maze.get_state_mut().collect_key(&metadata.position);
-                        commands.entity(maze_visual_state.entities[metadata.id]).despawn();
+                        events.door_unlocked.send(DoorUnlocked);
                         true
                     },
-                    CollidableType::Exit => {
-                        true
-                    },
-                    CollidableType::Field => {
-                        maze_visual_state.maze.get_state_mut().move_to(&metadata.position);
-                        true
+                    UnlockDoor::NoDoor => true,
+                    UnlockDoor::NoKey => {
+                        events.block(position, next);
+                        false
                     }
                 };
-            }
-            if flag_y && collide(
-                coll_pos,
-                coll_dim,
-                y_pos,
-            player_dim * 0.9).is_some(){
-                flag_y = match metadata.c_type{
-                    CollidableType::Wall => false,
-                    CollidableType::Door => {
-                        let my_position = maze_visual_state.maze.get_state_mut().position.clone();
-                        let door_position = ((my_position.0 as i32 - velocity.y.signum() as i32) as usize, my_position.1);
-                        match maze_visual_state.maze.get_state_mut().unlock_door(&door_position){
-                            UnlockDoor::Unlocked =>{
-                                commands.entity(maze_visual_state.entities[metadata.id]).despawn();
-                                true
-                            },
-                            _ => {
-                                false
-                            }
-                        }
-                    },
-                    CollidableType::Key => {
-                        maze_visual_state.maze.get_state_mut().collect_key(&metadata.position);
-                        commands.entity(maze_visual_state.entities[metadata.id]).despawn();
-                        true
-                    },
-                    CollidableType::Exit => {
-                        true
-                    },
-                    CollidableType::Field => {
-                        maze_visual_state.maze.get_state_mut().move_to(&metadata.position);
-                        true
+                if passable {
+                    new_along = target;
+                    if (target - (center_along + sign * span / 2.)) * sign > 0. {
+                        let state = maze_visual_state.maze.get_state_mut();
+                        let collected = state.keys_set.contains(&next);
+                        state.move_to(&next);
+                        state.collect_key(&next);
+                        announce_arrival(commands, events, maze_visual_state, collected);
                     }
-                };
+                }
             }
         }
-        let player_top_left = (translation.x + dx - player_dim.x / 2., translation.y + dy + player_dim.y / 2.);
-        let player_bottom_right = (translation.x + dx + player_dim.x / 2., translation.y + dy - player_dim.y / 2.);
+    }
+
+    if horizontal {
+        translation.x = new_along;
+        translation.y = new_across;
+    }else{
+        translation.y = new_along;
+        translation.x = new_across;
+    }
+}
 
-        if flag_x && player_bottom_right.0 <= maze_visual_state.size.x / 2. && player_top_left.0 >= -maze_visual_state.size.x / 2.{
-		    translation.x += dx;
+fn player_movement_system(
+    mut commands: Commands,
+    mut events: GameplayEvents,
+	mut player: Query<(&Velocity, &mut Transform, &Dimensions), With<Player>>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    player_state: Res<PlayerState>,
+    theme: Res<Theme>,
+    time: Res<Time>
+) {
+    if player_state.movement != Movement::Free {
+        return;
+    }
+    if let Ok((velocity, mut transform, player_dim)) = player.get_single_mut(){
+        let dx = velocity.x * time.delta_seconds() * theme.base_speed;
+        let dy = velocity.y * time.delta_seconds() * theme.base_speed;
+        if dx == 0. && dy == 0. {
+            *events.blocked = None;
         }
 
-        if flag_y && player_top_left.1 <= maze_visual_state.size.y / 2. && player_bottom_right.1 >= -maze_visual_state.size.y / 2.{
-		    translation.y += dy;
+        // The hitbox never gets wider than most of a field, otherwise large mazes would have no room to move
+        let half = (Vec2::new(player_dim.x, player_dim.y) * 0.9 / 2.).min(maze_visual_state.field_dimensions * 0.4);
+
+        if dx != 0. {
+            step_axis(&mut commands, &mut events, &mut maze_visual_state, &mut transform.translation, half, dx, true);
+        }
+        if dy != 0. {
+            step_axis(&mut commands, &mut events, &mut maze_visual_state, &mut transform.translation, half, dy, false);
         }
     }
 }
 
-fn inventory_sync_system(
-    query: Query<Entity, With<Inventory>>,
+fn player_grid_step_system(
     mut commands: Commands,
+    mut events: GameplayEvents,
+    actions: Res<Actions>,
+    time: Res<Time>,
+	mut player: Query<(&Velocity, &mut Transform), With<Player>>,
     mut maze_visual_state: ResMut<MazeVisualState>,
-    game_textures: Res<GameTextures>
+    mut player_state: ResMut<PlayerState>
+) {
+    let (velocity, mut transform) = match player.get_single_mut() {
+        Ok(player) => player,
+        Err(_) => return
+    };
+
+    if let Some(step) = player_state.step.as_mut() {
+        step.elapsed += time.delta_seconds();
+        let t = (step.elapsed / STEP_SECONDS).min(1.);
+        let position = step.from.lerp(step.to, t);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        if t < 1. {
+            return;
+        }
+        if let Some(state) = step.state.take() {
+            let collected = maze_visual_state.maze.get_state_mut().keys_set.len() > state.keys_set.len();
+            maze_visual_state.maze.state = Some(state);
+            if step.unlocked {
+                events.door_unlocked.send(DoorUnlocked);
+            }
+            announce_arrival(&mut commands, &mut events, &mut maze_visual_state, collected);
+        }
+        player_state.step = None;
+    }
+    if player_state.movement != Movement::Grid {
+        return;
+    }
+
+    let pressed = [(Action::MoveLeft, 0), (Action::MoveRight, 1), (Action::MoveUp, 2), (Action::MoveDown, 3)].into_iter()
+        .find(|(action, _)| actions.just_pressed(*action))
+        .map(|(_, direction)| direction);
+    let direction = match pressed {
+        Some(direction) => direction,
+        None if velocity.x.abs() >= velocity.y.abs() && velocity.x != 0. => if velocity.x < 0. { 0 } else { 1 },
+        None if velocity.y != 0. => if velocity.y > 0. { 2 } else { 3 },
+        None => return
+    };
+
+    let state = maze_visual_state.maze.get_state_mut().clone();
+    let field = &maze_visual_state.maze.fields[state.position.0][state.position.1];
+    let next = match maze_visual_state.maze.get_neighbour(&state.position, direction) {
+        Some(next) if !field.walls[direction] => next,
+        _ => return
+    };
+    let new_state = match state.transfer_state(&next) {
+        Some(new_state) => new_state,
+        None => {
+            events.door_blocked.send(DoorBlocked);
+            return;
+        }
+    };
+    let unlocked = state.doors_graph.get(&state.position).is_some_and(|doors| doors.contains(&next));
+    if unlocked {
+        if let Some(door) = maze_visual_state.doors.remove(&(state.position, direction)){
+            commands.entity(door).despawn();
+        }
+    }
+    player_state.step = Some(GridStep{
+        from: maze_visual_state.field_center(state.position),
+        to: maze_visual_state.field_center(next),
+        elapsed: 0.,
+        state: Some(new_state),
+        unlocked
+    });
+}
+
+// The player keeps its field, any offset inside it is dropped
+fn player_relayout_system(
+    mut relayout: EventReader<Relayout>,
+    mut player_state: ResMut<PlayerState>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+	mut query: Query<&mut Transform, With<Player>>
 ){
-    for e in query.iter(){
-        commands
-        .entity(e)
-        .insert(TextBundle{
-            text: Text::from_section(
-                format!("   Keys: {}", maze_visual_state.maze.get_state_mut().keys),
-                TextStyle {
-                    font_size: 30.,
-                    color: Color::rgb(0.,0.,0.),
-                    font: game_textures.font.clone()
-                }
-            ),
-            z_index: ZIndex::Global(30),
-            ..default()
-        });
+    if relayout.iter().count() == 0 {
+        return;
+    }
+    let position = maze_visual_state.maze.get_state_mut().position;
+    let center = maze_visual_state.field_center(position);
+    if let Some(step) = player_state.step.as_mut() {
+        step.from = center;
+        step.to = step.state.as_ref().map(|state| maze_visual_state.field_center(state.position)).unwrap_or(center);
+    }
+    if let Ok(mut transform) = query.get_single_mut() {
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+    }
+}
+
+// Only picking up a key or spending one on a door changes the count, undoing a move redraws the maze and shows it again
+fn inventory_sync_system(
+    mut key_collected: EventReader<KeyCollected>,
+    mut door_unlocked: EventReader<DoorUnlocked>,
+    mut relayout: EventReader<Relayout>,
+    mut maze_visual_state: ResMut<MazeVisualState>,
+    theme: Res<Theme>,
+    mut query: Query<&mut Text, With<Inventory>>
+){
+    let changed = key_collected.iter().count() + door_unlocked.iter().count() + relayout.iter().count();
+    if changed == 0 {
+        return;
+    }
+    let keys = maze_visual_state.maze.get_state_mut().keys;
+    for mut text in query.iter_mut(){
+        text.sections[0].value = format!("   Keys: {keys}");
+        text.sections[0].style.color = Theme::color(theme.text);
     }
 }
 
 fn setup_inventory_system(
     mut commands: Commands,
     game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
     mut maze_visual_state: ResMut<MazeVisualState>
 ){
     commands.spawn_empty()
     .insert(Inventory)
+    .insert(MazeVisual)
     .insert(TextBundle{
         text: Text::from_section(
-            format!("   Keys collected: {}", maze_visual_state.maze.get_state_mut().keys),
+            format!("   Keys: {}", maze_visual_state.maze.get_state_mut().keys),
             TextStyle {
                 font_size: 30.,
-                color: Color::rgb(0.,0.,0.),
+                color: Theme::color(theme.text),
                 font: game_textures.font.clone()
             }
         ),
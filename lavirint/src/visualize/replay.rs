@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::maze::{
+    Maze, solver::Solver, search::SearchTrace, state::State as MazeState,
+    layout::{hex_to_rgb, Layout, Piece}
+};
+
+use super::{AppState, WinSize, GameTextures, config::Theme, input::{Actions, Action, Bindings}, maze_visual::MazeVisualState};
+
+const REPLAY_SPEED: f32 = 20.;
+const REPLAY_MAX_SPEED: f32 = 2000.;
+const REPLAY_DEEP_COLOR: &str = "3B2C85";
+
+pub struct ReplayPlugin;
+
+#[derive(Component)]
+pub struct ReplayScreen;
+
+#[derive(Component)]
+pub struct ReplayCell{
+    pub panel: usize,
+    pub position: (usize, usize)
+}
+
+#[derive(Component)]
+pub struct ReplayText{
+    pub panel: usize
+}
+
+pub struct ReplayPanel{
+    pub solver: Solver,
+    pub trace: SearchTrace,
+    pub first_expansion: HashMap<(usize, usize), (usize, usize)>,
+    pub max_depth: usize
+}
+
+impl ReplayPanel{
+    fn new(solver: Solver, maze: &Maze, state: MazeState) -> Self{
+        let trace = solver.trace(maze, state);
+        let mut first_expansion = HashMap::new();
+        for (index, expansion) in trace.expansions.iter().enumerate(){
+            first_expansion.entry(expansion.position).or_insert((index, expansion.depth));
+        }
+        let max_depth = trace.expansions.iter().map(|expansion| expansion.depth).max().unwrap_or(0);
+        Self{ solver, trace, first_expansion, max_depth }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ReplayState{
+    pub start: MazeState,
+    pub panels: Vec<ReplayPanel>,
+    pub progress: f32,
+    pub speed: f32,
+    pub paused: bool
+}
+
+impl Plugin for ReplayPlugin{
+    fn build(&self, app: &mut App){
+        app.insert_resource(ReplayState::default())
+        .add_system_set(SystemSet::on_update(AppState::Playing).with_system(replay_start_system))
+        .add_system_set(SystemSet::on_enter(AppState::Replay).with_system(replay_spawn_system))
+        .add_system_set(
+            SystemSet::on_update(AppState::Replay)
+            .with_system(replay_keyboard_event_system.label("replay-input"))
+            .with_system(replay_playback_system.after("replay-input"))
+        )
+        .add_system_set(SystemSet::on_exit(AppState::Replay).with_system(replay_despawn_system));
+    }
+}
+
+fn depth_color(depth: usize, max_depth: usize, theme: &Theme) -> Color{
+    let t = if max_depth == 0 { 0. } else { depth as f32 / max_depth as f32 };
+    let (from, to) = (theme.solution, hex_to_rgb(REPLAY_DEEP_COLOR));
+    let channel = |index: usize| (from[index] as f32 + (to[index] as f32 - from[index] as f32) * t) / 255.;
+    Color::rgb(channel(0), channel(1), channel(2))
+}
+
+fn other_solver(solver: Solver) -> Solver{
+    match solver {
+        Solver::AStar => Solver::Sequential,
+        _ => Solver::AStar
+    }
+}
+
+fn next_solver(solver: Solver, skip: Solver) -> Solver{
+    let index = Solver::ALL.iter().position(|s| *s == solver).unwrap_or(0);
+    let next = Solver::ALL[(index + 1) % Solver::ALL.len()];
+    if next == skip { next_solver(next, skip) } else { next }
+}
+
+fn replay_start_system(
+    mut actions: ResMut<Actions>,
+    mut app_state: ResMut<State<AppState>>,
+    mut replay_state: ResMut<ReplayState>,
+    mut maze_visual_state: ResMut<MazeVisualState>
+){
+    if !actions.clear_just_pressed(Action::Replay){
+        return;
+    }
+    let solver = maze_visual_state.solver;
+    let start = maze_visual_state.maze.get_state_mut().clone();
+    let maze = &maze_visual_state.maze;
+    *replay_state = ReplayState{
+        panels: vec![
+            ReplayPanel::new(solver, maze, start.clone()),
+            ReplayPanel::new(other_solver(solver), maze, start.clone())
+        ],
+        start,
+        progress: 0.,
+        speed: REPLAY_SPEED,
+        paused: false
+    };
+    app_state.push(AppState::Replay).unwrap_or_default();
+}
+
+fn replay_spawn_system(
+    mut commands: Commands,
+    replay_state: Res<ReplayState>,
+    maze_visual_state: Res<MazeVisualState>,
+    game_textures: Res<GameTextures>,
+    theme: Res<Theme>,
+    bindings: Res<Bindings>,
+    win_size: Res<WinSize>
+){
+    let maze = &maze_visual_state.maze;
+    let frame_size = win_size.frame_size;
+    let panel_count = replay_state.panels.len() as f32;
+    let panel_w = (win_size.w - (panel_count + 1.) * frame_size) / panel_count;
+    let panel_h = win_size.h - 3. * frame_size;
+    let layout = Layout::fit(maze, panel_w, panel_h);
+    let thickness = layout.field.0.min(layout.field.1);
+    let layout = Layout{ wall_size: thickness * theme.wall_scale, door_size: thickness * theme.door_scale, ..layout };
+    let text_style = TextStyle{
+        font_size: 24.,
+        color: Theme::color(theme.text),
+        font: game_textures.font.clone()
+    };
+
+    commands.spawn(SpriteBundle{
+        sprite: Sprite{
+            color: Theme::color(theme.background),
+            custom_size: Some(Vec2::new(win_size.w, win_size.h)),
+            ..default()
+        },
+        transform: Transform::from_translation(Vec3::new(0., 0., 20.)),
+        ..default()
+    })
+    .insert(ReplayScreen);
+
+    for panel in 0..replay_state.panels.len(){
+        let left = -win_size.w / 2. + frame_size + panel as f32 * (panel_w + frame_size);
+        let top = win_size.h / 2. - 2. * frame_size;
+
+        for (y, row) in maze.fields.iter().enumerate(){
+            for x in 0..row.len(){
+                let rect = layout.field_rect((y, x));
+                commands.spawn(SpriteBundle{
+                    sprite: Sprite{
+                        color: Theme::color(theme.field),
+                        custom_size: Some(Vec2::new(rect.w, rect.h)),
+                        anchor: Anchor::TopLeft,
+                        ..default()
+                    },
+                    transform: Transform::from_translation(Vec3::new(left + rect.x, top - rect.y, 21.)),
+                    ..default()
+                })
+                .insert(ReplayCell{ panel, position: (y, x) })
+                .insert(ReplayScreen);
+            }
+        }
+
+        for (piece, _, rect) in layout.pieces(maze){
+            commands.spawn(SpriteBundle{
+                sprite: Sprite{
+                    color: Theme::color(if piece == Piece::Door { theme.door } else { theme.wall }),
+                    custom_size: Some(Vec2::new(rect.w, rect.h)),
+                    anchor: Anchor::TopLeft,
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(left + rect.x, top - rect.y, 22.)),
+                ..default()
+            })
+            .insert(ReplayScreen);
+        }
+
+        commands.spawn(Text2dBundle{
+            text: Text::from_section("", text_style.clone()).with_alignment(TextAlignment::CENTER),
+            transform: Transform::from_translation(Vec3::new(left + panel_w / 2., top + frame_size / 2., 23.)),
+            ..default()
+        })
+        .insert(ReplayText{ panel })
+        .insert(ReplayScreen);
+    }
+
+    commands.spawn(Text2dBundle{
+        text: Text::from_section(
+            format!(
+                "{}/{} - speed   {} - pause   {} - restart   {} - change solver   {}/{} - back",
+                bindings.name(Action::MoveUp), bindings.name(Action::MoveDown), bindings.name(Action::PauseReplay), bindings.name(Action::Restart),
+                bindings.name(Action::SwitchSolver), bindings.name(Action::Back), bindings.name(Action::Replay)
+            ),
+            TextStyle{ font_size: 20., ..text_style }
+        ).with_alignment(TextAlignment::CENTER),
+        transform: Transform::from_translation(Vec3::new(0., -win_size.h / 2. + frame_size / 2., 23.)),
+        ..default()
+    })
+    .insert(ReplayScreen);
+}
+
+fn replay_keyboard_event_system(
+    mut actions: ResMut<Actions>,
+    mut app_state: ResMut<State<AppState>>,
+    mut replay_state: ResMut<ReplayState>,
+    maze_visual_state: Res<MazeVisualState>
+){
+    if actions.clear_just_pressed(Action::Back) || actions.clear_just_pressed(Action::Pause) || actions.clear_just_pressed(Action::Replay){
+        app_state.pop().unwrap_or_default();
+    }else if actions.just_pressed(Action::MoveUp){
+        replay_state.speed = (replay_state.speed * 2.).min(REPLAY_MAX_SPEED);
+    }else if actions.just_pressed(Action::MoveDown){
+        replay_state.speed = (replay_state.speed / 2.).max(1.);
+    }else if actions.just_pressed(Action::PauseReplay){
+        replay_state.paused = !replay_state.paused;
+    }else if actions.just_pressed(Action::Restart){
+        replay_state.progress = 0.;
+    }else if actions.just_pressed(Action::SwitchSolver) && replay_state.panels.len() > 1{
+        let solver = next_solver(replay_state.panels[1].solver, replay_state.panels[0].solver);
+        let start = replay_state.start.clone();
+        replay_state.panels[1] = ReplayPanel::new(solver, &maze_visual_state.maze, start);
+        replay_state.progress = 0.;
+    }
+}
+
+fn replay_playback_system(
+    time: Res<Time>,
+    theme: Res<Theme>,
+    mut replay_state: ResMut<ReplayState>,
+    mut cells: Query<(&ReplayCell, &mut Sprite)>,
+    mut texts: Query<(&ReplayText, &mut Text)>
+){
+    if !replay_state.paused{
+        let longest = replay_state.panels.iter().map(|panel| panel.trace.expansions.len()).max().unwrap_or(0) as f32;
+        replay_state.progress = (replay_state.progress + replay_state.speed * time.delta_seconds()).min(longest);
+    }
+    let progress = replay_state.progress as usize;
+
+    for (cell, mut sprite) in cells.iter_mut(){
+        let panel = &replay_state.panels[cell.panel];
+        let finished = progress >= panel.trace.expansions.len();
+        sprite.color = match panel.first_expansion.get(&cell.position) {
+            _ if finished && panel.trace.path.as_ref().is_some_and(|path| path.contains(&cell.position)) => Theme::color(theme.key),
+            Some((index, depth)) if *index < progress => depth_color(*depth, panel.max_depth, &theme),
+            _ => Theme::color(theme.field)
+        };
+    }
+
+    for (label, mut text) in texts.iter_mut(){
+        let panel = &replay_state.panels[label.panel];
+        let total = panel.trace.expansions.len();
+        let shown = progress.min(total);
+        let mut value = format!("{}: {shown}/{total} expanded", panel.solver.name());
+        if shown > 0 {
+            value += format!(", depth {}", panel.trace.expansions[shown - 1].depth).as_str();
+        }
+        if shown == total {
+            value += match &panel.trace.path {
+                Some(path) => format!(", path {} moves", path.len() - 1),
+                None => ", no path".to_string()
+            }.as_str();
+        }
+        if replay_state.paused {
+            value += " (paused)";
+        }
+        text.sections[0].value = value;
+    }
+}
+
+fn replay_despawn_system(
+    mut commands: Commands,
+    query: Query<Entity, With<ReplayScreen>>
+){
+    for e in query.iter(){
+        commands.entity(e).despawn_recursive();
+    }
+}
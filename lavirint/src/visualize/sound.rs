@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use super::{AppState, config::Theme, player::{KeyCollected, DoorUnlocked, DoorBlocked, ExitReached}};
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin{
+    fn build(&self, app: &mut App){
+        app.add_system_set(SystemSet::on_update(AppState::Playing).with_system(sound_system.after("movement")));
+    }
+}
+
+// One sound per kind of event and frame, a burst of the same event would only play over itself
+fn sound_system(
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    mut key_collected: EventReader<KeyCollected>,
+    mut door_unlocked: EventReader<DoorUnlocked>,
+    mut door_blocked: EventReader<DoorBlocked>,
+    mut exit_reached: EventReader<ExitReached>
+){
+    let sounds = [
+        (key_collected.iter().count(), &theme.sounds.key),
+        (door_unlocked.iter().count(), &theme.sounds.door),
+        (door_blocked.iter().count(), &theme.sounds.blocked),
+        (exit_reached.iter().count(), &theme.sounds.exit)
+    ];
+    for (count, sound) in sounds{
+        if let (true, Some(path)) = (count > 0, sound) {
+            audio.play(asset_server.load(path.as_str()));
+        }
+    }
+}
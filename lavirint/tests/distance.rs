@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use maze::maze::{Maze, generator::GeneratorOptions, state::State};
+
+fn load(file: &str) -> Maze{
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(file);
+    Maze::parse_from_path(&path.to_string_lossy().to_string()).unwrap()
+}
+
+fn shortest_moves(maze: &Maze, state: &State) -> Option<usize>{
+    maze.search_for_shortest_path(state.clone()).map(|path| path.len() - 1)
+}
+
+// The reverse search has to agree with a forward search from every state it reaches on the way
+fn assert_matches_forward_search(maze: &Maze){
+    let field = maze.distance_field();
+    let mut state = State::create_from_maze(maze);
+    assert_eq!(field.moves_left(&state), shortest_moves(maze, &state));
+    while let Some(next) = field.next_move(maze, &state){
+        let moves = field.moves_left(&state).unwrap();
+        state = state.transfer_state(&next).unwrap();
+        assert_eq!(field.moves_left(&state), Some(moves - 1));
+        assert_eq!(field.moves_left(&state), shortest_moves(maze, &state));
+    }
+    if field.moves_left(&State::create_from_maze(maze)).is_some() {
+        assert!(maze.exits.contains(&state.position));
+    }
+}
+
+#[test]
+fn distances_match_the_forward_search_on_levels(){
+    for file in ["primer.txt", "levels/corridors.txt", "levels/vault.txt"]{
+        assert_matches_forward_search(&load(file));
+    }
+}
+
+#[test]
+fn distances_match_the_forward_search_on_generated_mazes(){
+    for seed in 0..50{
+        assert_matches_forward_search(&Maze::generate(&GeneratorOptions{ seed, dimensions: (5, 6), doors: 3, keys: 2, exits: 2 }));
+    }
+}
+
+#[test]
+fn exits_are_zero_and_the_start_cell_matches_its_state(){
+    let maze = load("levels/corridors.txt");
+    let field = maze.distance_field();
+    let best = field.best();
+    for exit in maze.exits.iter(){
+        assert_eq!(best.get(exit), Some(&0));
+    }
+    let start = State::create_from_maze(&maze);
+    assert_eq!(field.get(start.position, start.keys), field.moves_left(&start));
+    assert!(field.max_distance() >= field.moves_left(&start).unwrap());
+}
+
+#[test]
+fn walled_in_start_has_no_distance(){
+    let mut maze = load("primer.txt");
+    maze.fields[0][0].walls = vec![true; 4];
+    maze.fields[0][1].walls[0] = true;
+    maze.fields[1][0].walls[2] = true;
+    let field = maze.distance_field();
+    let start = State::create_from_maze(&maze);
+    assert_eq!(field.moves_left(&start), None);
+    assert_eq!(field.next_move(&maze, &start), None);
+    assert_eq!(field.dead_ends(), 1);
+}
@@ -0,0 +1,73 @@
+use std::{env, fs, io::ErrorKind, path::PathBuf};
+
+use maze::maze::{Maze, format::MazeFormat, generator::GeneratorOptions};
+
+fn load(file: &str) -> Maze{
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(file);
+    Maze::parse_from_path(&path.to_string_lossy().to_string()).unwrap()
+}
+
+fn temp_path(name: &str) -> String{
+    env::temp_dir().join(format!("lavirint-{}-{name}", std::process::id())).to_string_lossy().to_string()
+}
+
+fn assert_same(a: &Maze, b: &Maze){
+    assert_eq!(a.dimensions, b.dimensions);
+    assert_eq!(a.exits, b.exits);
+    assert_eq!(a.get_keys_set(), b.get_keys_set());
+    assert_eq!(a.to_txt_string(), b.to_txt_string());
+}
+
+#[test]
+fn every_format_round_trips_the_levels(){
+    for file in ["primer.txt", "levels/corridors.txt", "levels/vault.txt"]{
+        let maze = load(file);
+        for (extension, format) in [("txt", MazeFormat::Txt), ("bin", MazeFormat::Bin), ("lab", MazeFormat::Lab)]{
+            let path = temp_path(&format!("round-trip.{extension}"));
+            maze.save_with_format(&path, format).unwrap();
+            let loaded = Maze::parse_from_path(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            assert_same(&maze, &loaded);
+        }
+    }
+}
+
+#[test]
+fn txt_output_matches_the_level_file(){
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("levels/corridors.txt");
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(load("levels/corridors.txt").to_txt_string().trim_end(), content.trim_end());
+}
+
+#[test]
+fn lab_keeps_any_size(){
+    for dimensions in [(1, 1), (3, 17), (20, 4)]{
+        let maze = Maze::generate(&GeneratorOptions{ seed: 3, dimensions, ..Default::default() });
+        assert_same(&maze, &Maze::parse_from_lab_vector(maze.to_lab_vector()).unwrap());
+    }
+}
+
+#[test]
+fn txt_and_bin_only_hold_the_default_size(){
+    let maze = Maze::generate(&GeneratorOptions{ seed: 3, dimensions: (3, 17), ..Default::default() });
+    for format in [MazeFormat::Txt, MazeFormat::Bin]{
+        let er = maze.save_with_format(&temp_path("too-large"), format).unwrap_err();
+        assert_eq!(er.kind(), ErrorKind::InvalidInput);
+        assert_eq!(maze.to_format_vector(format).err().map(|er| er.kind()), Some(ErrorKind::InvalidInput));
+    }
+}
+
+#[test]
+fn broken_lab_data_is_rejected(){
+    for data in [vec![0, 1], vec![0, 0, 0, 5], vec![0, 2, 0, 2, 0xFF]]{
+        assert_eq!(Maze::parse_from_lab_vector(data).err().map(|er| er.kind()), Some(ErrorKind::InvalidData));
+    }
+}
+
+#[test]
+fn format_comes_from_the_extension(){
+    assert_eq!(MazeFormat::from_path(&"levels/vault.txt".to_string()).unwrap(), MazeFormat::Txt);
+    assert_eq!(MazeFormat::from_path(&"maze.LAB".to_string()).unwrap(), MazeFormat::Lab);
+    assert!(MazeFormat::from_path(&"maze".to_string()).is_err());
+    assert!(MazeFormat::from_path(&"maze.png".to_string()).is_err());
+}
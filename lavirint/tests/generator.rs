@@ -0,0 +1,43 @@
+use maze::maze::{Maze, generator::GeneratorOptions, validation::Severity};
+
+fn assert_valid(options: &GeneratorOptions){
+    let maze = Maze::generate(options);
+    let errors: Vec<String> = maze.validate().iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .map(|issue| issue.to_string())
+        .collect();
+    assert!(errors.is_empty(), "Seed {} with {options:?} generated an invalid maze: {errors:?}", options.seed);
+}
+
+#[test]
+fn default_options_stay_solvable(){
+    for seed in 0..=300{
+        assert_valid(&GeneratorOptions{ seed, ..Default::default() });
+    }
+}
+
+#[test]
+fn doors_without_extra_keys_stay_solvable(){
+    for seed in 0..=300{
+        assert_valid(&GeneratorOptions{ seed, doors: 4, keys: 0, ..Default::default() });
+    }
+}
+
+#[test]
+fn small_and_crowded_mazes_stay_solvable(){
+    for seed in 0..100{
+        assert_valid(&GeneratorOptions{ seed, dimensions: (2, 3), doors: 5, keys: 1, exits: 2 });
+        assert_valid(&GeneratorOptions{ seed, dimensions: (1, 1), doors: 1, keys: 0, exits: 1 });
+    }
+}
+
+#[test]
+fn same_seed_same_maze(){
+    let options = GeneratorOptions{ seed: 42, ..Default::default() };
+    assert_eq!(Maze::generate(&options).to_string(), Maze::generate(&options).to_string());
+}
+
+#[test]
+fn large_maze_validates(){
+    assert_valid(&GeneratorOptions{ seed: 1, dimensions: (40, 40), doors: 6, keys: 3, exits: 2 });
+}
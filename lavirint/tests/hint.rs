@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use maze::maze::{Maze, generator::GeneratorOptions};
+
+fn load(file: &str) -> Maze{
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(file);
+    Maze::parse_from_path(&path.to_string_lossy().to_string()).unwrap()
+}
+
+// Taking the hinted move every time has to reach an exit in exactly the number of moves the first hint promised
+fn assert_hints_lead_out(maze: &Maze){
+    let field = maze.distance_field();
+    let mut state = maze.clone().get_state_mut().clone();
+    let shortest = maze.search_for_shortest_path(state.clone()).map(|path| path.len() - 1);
+    let first = field.hint(maze, &state);
+    assert_eq!(first.map(|hint| hint.moves), shortest);
+
+    let mut moves = 0;
+    let mut expected_key = first.and_then(|hint| hint.next_key);
+    while let Some(hint) = field.hint(maze, &state){
+        assert_eq!(hint.moves + moves, first.unwrap().moves);
+        let next = match hint.next {
+            Some(next) => next,
+            None => break
+        };
+        let after = state.transfer_state(&next).unwrap();
+        if after.keys_set.len() < state.keys_set.len() {
+            assert_eq!(expected_key, Some(next));
+            expected_key = field.hint(maze, &after).and_then(|hint| hint.next_key);
+        }
+        state = after;
+        moves += 1;
+    }
+    assert!(maze.exits.contains(&state.position) || first.is_none());
+}
+
+#[test]
+fn hints_follow_the_shortest_path_in_levels(){
+    for file in ["primer.txt", "levels/corridors.txt", "levels/vault.txt"]{
+        assert_hints_lead_out(&load(file));
+    }
+}
+
+#[test]
+fn hints_follow_the_shortest_path_in_generated_mazes(){
+    for seed in 0..50{
+        assert_hints_lead_out(&Maze::generate(&GeneratorOptions{ seed, dimensions: (5, 6), doors: 3, keys: 2, exits: 1 }));
+    }
+}
+
+#[test]
+fn no_move_left_on_an_exit(){
+    let mut maze = load("primer.txt");
+    let mut state = maze.get_state_mut().clone();
+    state.position = *maze.exits.iter().next().unwrap();
+    let hint = maze.distance_field_from(&state).hint(&maze, &state).unwrap();
+    assert_eq!((hint.moves, hint.next, hint.next_key), (0, None, None));
+}
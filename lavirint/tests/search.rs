@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use maze::maze::{Maze, generator::GeneratorOptions, solver::Solver, state::State};
+
+fn load(file: &str) -> Maze{
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(file);
+    Maze::parse_from_path(&path.to_string_lossy().to_string()).unwrap()
+}
+
+// A path is only valid if every step is a passage and every door on it has a key by the time it's reached
+fn assert_walkable(maze: &Maze, path: &[(usize, usize)]){
+    let mut state = State::create_from_maze(maze);
+    assert_eq!(path.first(), Some(&maze.start));
+    for position in path.iter().skip(1){
+        assert!(maze.get_direct_neighbours(&state.position).contains(position), "No passage from {:?} to {position:?}", state.position);
+        state = state.transfer_state(position).unwrap_or_else(|| panic!("Door to {position:?} is still locked"));
+    }
+    assert!(maze.exits.contains(&state.position));
+}
+
+fn assert_a_star_is_optimal(maze: &Maze){
+    let state = State::create_from_maze(maze);
+    let breadth_first = maze.search_for_shortest_path(state.clone());
+    let a_star = Solver::AStar.solve(maze, state);
+    assert_eq!(a_star.as_ref().map(|path| path.len()), breadth_first.map(|path| path.len()));
+    if let Some(path) = a_star {
+        assert_walkable(maze, &path);
+    }
+}
+
+#[test]
+fn a_star_matches_breadth_first_on_levels(){
+    for file in ["primer.txt", "levels/corridors.txt", "levels/vault.txt"]{
+        assert_a_star_is_optimal(&load(file));
+    }
+}
+
+#[test]
+fn a_star_matches_breadth_first_on_generated_mazes(){
+    for seed in 0..100{
+        assert_a_star_is_optimal(&Maze::generate(&GeneratorOptions{ seed, ..Default::default() }));
+        assert_a_star_is_optimal(&Maze::generate(&GeneratorOptions{ seed, dimensions: (4, 5), doors: 4, keys: 1, exits: 2 }));
+    }
+}
+
+#[test]
+fn a_star_expands_no_more_than_breadth_first(){
+    for file in ["primer.txt", "levels/corridors.txt", "levels/vault.txt"]{
+        let maze = load(file);
+        let state = State::create_from_maze(&maze);
+        let a_star = Solver::AStar.trace(&maze, state.clone());
+        let breadth_first = Solver::Sequential.trace(&maze, state);
+        assert!(a_star.expansions.len() <= breadth_first.expansions.len(), "{file}: {} > {}", a_star.expansions.len(), breadth_first.expansions.len());
+    }
+}
+
+#[test]
+fn a_star_reports_unsolvable_mazes(){
+    let mut maze = load("primer.txt");
+    maze.fields[0][0].walls = vec![true; 4];
+    maze.fields[0][1].walls[0] = true;
+    maze.fields[1][0].walls[2] = true;
+    assert_a_star_is_optimal(&maze);
+    assert!(Solver::AStar.solve(&maze, State::create_from_maze(&maze)).is_none());
+}
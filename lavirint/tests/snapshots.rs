@@ -0,0 +1,80 @@
+use std::{env, fs, path::PathBuf};
+
+use maze::maze::{Maze, raster::{Image, RasterOptions}};
+
+// Equally short paths can come back in any order from the solvers, so the overlays use fixed ones
+const PRIMER_PATH: &[(usize, usize)] = &[(0, 0), (1, 0), (1, 1), (1, 2), (2, 2), (2, 3), (2, 2), (3, 2), (4, 2), (5, 2)];
+const CORRIDORS_PATH: &[(usize, usize)] = &[
+    (0, 0), (1, 0), (1, 1), (0, 1), (0, 2), (0, 3), (0, 4), (1, 4), (2, 4), (2, 3), (3, 3), (3, 2), (3, 1), (3, 0),
+    (4, 0), (5, 0), (5, 1), (5, 2), (5, 3), (4, 3), (4, 4), (5, 4), (5, 5), (4, 5), (4, 6), (4, 7), (4, 8), (5, 8)
+];
+
+// Run with UPDATE_SNAPSHOTS=1 to accept the current renders
+fn assert_snapshot(name: &str, image: &Image){
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots");
+    let expected_path = dir.join(format!("{name}.png"));
+    let actual_path = dir.join(format!("{name}.actual.png"));
+
+    if env::var("UPDATE_SNAPSHOTS").is_ok() {
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&expected_path, image.to_png().unwrap()).unwrap();
+        let _ = fs::remove_file(&actual_path);
+        return;
+    }
+
+    let expected = fs::read(&expected_path)
+        .unwrap_or_else(|er| panic!("Missing snapshot {}: {er}, run with UPDATE_SNAPSHOTS=1 to create it", expected_path.display()));
+    let expected = Image::from_png(&expected).unwrap();
+    if expected != *image {
+        fs::write(&actual_path, image.to_png().unwrap()).unwrap();
+        let differing = expected.pixels.chunks(3).zip(image.pixels.chunks(3)).filter(|(a, b)| a != b).count();
+        panic!(
+            "Render of '{name}' differs from the snapshot ({}x{} vs {}x{}, {differing} pixels), see {}",
+            image.width, image.height, expected.width, expected.height, actual_path.display()
+        );
+    }
+    let _ = fs::remove_file(&actual_path);
+}
+
+fn load(file: &str) -> Maze{
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(file);
+    Maze::parse_from_path(&path.to_string_lossy().to_string()).unwrap()
+}
+
+fn assert_shortest(maze: &mut Maze, path: &[(usize, usize)]){
+    let state = maze.get_state_mut().clone();
+    assert_eq!(maze.search_for_shortest_path(state).map(|found| found.len()), Some(path.len()));
+}
+
+#[test]
+fn primer(){
+    let maze = load("primer.txt");
+    assert_snapshot("primer", &maze.rasterize(&RasterOptions::default()));
+}
+
+#[test]
+fn primer_with_solution_and_player(){
+    let mut maze = load("primer.txt");
+    assert_shortest(&mut maze, PRIMER_PATH);
+    let options = RasterOptions{ player: Some(PRIMER_PATH[5]), path: Some(PRIMER_PATH), ..Default::default() };
+    assert_snapshot("primer-solution", &maze.rasterize(&options));
+}
+
+#[test]
+fn corridors_with_solution(){
+    let mut maze = load("levels/corridors.txt");
+    assert_shortest(&mut maze, CORRIDORS_PATH);
+    assert_snapshot("corridors-solution", &maze.rasterize(&RasterOptions{ path: Some(CORRIDORS_PATH), ..Default::default() }));
+}
+
+#[test]
+fn vault_large_cells(){
+    let maze = load("levels/vault.txt");
+    assert_snapshot("vault", &maze.rasterize(&RasterOptions{ cell_size: 64, player: Some(maze.start), ..Default::default() }));
+}
+
+#[test]
+fn png_round_trip(){
+    let image = load("primer.txt").rasterize(&RasterOptions{ cell_size: 8, ..Default::default() });
+    assert_eq!(Image::from_png(&image.to_png().unwrap()).unwrap(), image);
+}
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use maze::maze::{Maze, validation::Severity};
+
+fn load(file: &str) -> Maze{
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(file);
+    Maze::parse_from_path(&path.to_string_lossy().to_string()).unwrap()
+}
+
+#[test]
+fn levels_have_no_errors(){
+    for file in ["primer.txt", "levels/corridors.txt", "levels/vault.txt"]{
+        assert!(load(file).validate().iter().all(|issue| issue.severity != Severity::Error), "{file} doesn't validate");
+    }
+}
+
+#[test]
+fn validation_reports_broken_mazes(){
+    let mut maze = load("primer.txt");
+
+    // A wall on one side only makes a one-way passage
+    maze.fields[0][0].walls[3] = !maze.fields[0][0].walls[3];
+    let issues = maze.validate();
+    assert!(issues.iter().any(|issue| issue.severity == Severity::Warning && issue.position == Some((0, 0))));
+
+    // Walling in the start cuts it off from every exit
+    maze.fields[0][0].walls = vec![true; 4];
+    let issues = maze.validate();
+    assert!(issues.iter().any(|issue| issue.severity == Severity::Error && issue.message.contains("no exit can be reached")));
+
+    for (i, j) in maze.exits.clone(){
+        maze.fields[i][j].exit = false;
+    }
+    maze.exits.clear();
+    let issues = maze.validate();
+    assert!(issues.iter().any(|issue| issue.severity == Severity::Error && issue.message.contains("doesn't have an exit")));
+}